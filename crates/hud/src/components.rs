@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use mechanics::{element::Element, PhysicsParameter};
+use view::{ToolCommand, ToolMode};
 
 #[derive(Component)]
 pub struct HudCamera;
@@ -23,3 +25,309 @@ pub struct ElementNeutronsText;
 
 #[derive(Component)]
 pub struct ElementElectronsText;
+
+#[derive(Component)]
+pub struct MassNumberText;
+
+#[derive(Component)]
+pub struct IsotopeText;
+
+#[derive(Component)]
+pub struct ChargeText;
+
+#[derive(Component)]
+pub struct ConstituentsText;
+
+/// Bottom-right panel listing the colours of the active
+/// [`ColorScheme`](view::ColorScheme)
+#[derive(Component)]
+pub struct ColorLegend;
+
+/// Panel showing the image of the [`NucleusInspector`](view::NucleusInspector)
+#[derive(Component)]
+pub struct NucleusInspectorPanel;
+
+/// Label over a nucleon in the inspector panel
+#[derive(Component)]
+pub struct NucleusLabel {
+    pub proxy: Entity,
+}
+
+#[derive(Component)]
+pub struct PeriodicTablePanel;
+
+/// Button for one element in the periodic table
+#[derive(Component)]
+pub struct PeriodicCell {
+    pub element: Element,
+}
+
+/// Current and peak count text of a [`PeriodicCell`]
+#[derive(Component)]
+pub struct PeriodicCellCounts {
+    pub element: Element,
+}
+
+/// What a button in the simulation control strip does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimControl {
+    TogglePause,
+    /// Pause and run this many fixed steps
+    Step(u32),
+    Speed(f32),
+}
+
+#[derive(Component)]
+pub struct SimControlButton {
+    pub control: SimControl,
+}
+
+#[derive(Component)]
+pub struct SimStatusText;
+
+#[derive(Component)]
+pub struct ParameterPanel;
+
+/// Track of a parameter slider, set by dragging along it
+#[derive(Component)]
+pub struct ParameterSlider {
+    pub parameter: PhysicsParameter,
+}
+
+/// Filled part of a [`ParameterSlider`], as wide as the value
+#[derive(Component)]
+pub struct ParameterSliderFill {
+    pub parameter: PhysicsParameter,
+}
+
+/// Numeric field of a parameter, clicked to type in a value
+#[derive(Component)]
+pub struct ParameterField {
+    pub parameter: PhysicsParameter,
+    /// Text typed so far, `None` unless the field is being edited
+    pub editing: Option<String>,
+}
+
+#[derive(Component)]
+pub struct ParameterFieldText {
+    pub parameter: PhysicsParameter,
+}
+
+/// What a button below the parameter rows does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterAction {
+    Reset,
+    SavePreset,
+}
+
+#[derive(Component)]
+pub struct ParameterActionButton {
+    pub action: ParameterAction,
+}
+
+#[derive(Component)]
+pub struct ParameterStatusText;
+
+/// A line the plot panel can draw from the stats history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlotSeries {
+    TotalEnergy,
+    Temperature,
+    FreeNucleons,
+    Atoms,
+    /// One line per element that has formed
+    Elements,
+}
+
+impl PlotSeries {
+    pub const ALL: [Self; 5] = [
+        Self::TotalEnergy,
+        Self::Temperature,
+        Self::FreeNucleons,
+        Self::Atoms,
+        Self::Elements,
+    ];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::TotalEnergy => "Energy",
+            Self::Temperature => "Temperature",
+            Self::FreeNucleons => "Free nucleons",
+            Self::Atoms => "Atoms",
+            Self::Elements => "Elements",
+        }
+    }
+
+    /// Line colour, elements use their own colours instead
+    pub fn color(self) -> Color {
+        match self {
+            Self::TotalEnergy => Color::srgb_u8(243, 139, 168), // red
+            Self::Temperature => Color::srgb_u8(250, 179, 135), // peach
+            Self::FreeNucleons => Color::srgb_u8(137, 180, 250), // blue
+            Self::Atoms => Color::srgb_u8(166, 227, 161),       // green
+            Self::Elements => Color::srgb_u8(205, 214, 244),    // text
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PlotPanel;
+
+/// Button that shows or hides a series, also listing its range
+#[derive(Component)]
+pub struct PlotToggle {
+    pub series: PlotSeries,
+}
+
+#[derive(Component)]
+pub struct PlotToggleText {
+    pub series: PlotSeries,
+}
+
+/// Sim time label under one end of the time axis
+#[derive(Component)]
+pub struct PlotTimeText {
+    pub latest: bool,
+}
+
+#[derive(Component)]
+pub struct AtomOutliner;
+
+/// Scrolling container of the [`OutlinerRow`]s
+#[derive(Component)]
+pub struct OutlinerList;
+
+/// One line of the outliner, showing `atom`. Rows are reused as the atoms
+/// change, so `atom` is reassigned on every refresh.
+#[derive(Component)]
+pub struct OutlinerRow {
+    pub atom: Entity,
+}
+
+#[derive(Component)]
+pub struct OutlinerRowText;
+
+/// Column the outliner is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutlinerSort {
+    #[default]
+    Element,
+    MassNumber,
+    Charge,
+    Age,
+}
+
+impl OutlinerSort {
+    pub const ALL: [Self; 4] =
+        [Self::Element, Self::MassNumber, Self::Charge, Self::Age];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Element => "Z",
+            Self::MassNumber => "A",
+            Self::Charge => "Charge",
+            Self::Age => "Age",
+        }
+    }
+}
+
+/// Sorts by `sort`, or reverses the order when it already is
+#[derive(Component)]
+pub struct OutlinerSortButton {
+    pub sort: OutlinerSort,
+}
+
+/// Cycles the element filter through the elements present
+#[derive(Component)]
+pub struct OutlinerFilterButton;
+
+#[derive(Component)]
+pub struct OutlinerFilterText;
+
+#[derive(Component)]
+pub struct OutlinerSummaryText;
+
+/// Details of the hovered particle, following the cursor
+#[derive(Component)]
+pub struct ParticleTooltip;
+
+#[derive(Component)]
+pub struct ParticleTooltipText;
+
+#[derive(Component)]
+pub struct PinnedParticlePanel;
+
+#[derive(Component)]
+pub struct PinnedParticleText;
+
+/// What a button in the pinned particle panel does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinnedParticleAction {
+    /// Select the atom the particle is bound in
+    SelectAtom,
+    Unpin,
+}
+
+#[derive(Component)]
+pub struct PinnedParticleButton {
+    pub action: PinnedParticleAction,
+}
+
+/// Picks the tool a click in the scene uses
+#[derive(Component)]
+pub struct ToolButton {
+    pub mode: ToolMode,
+}
+
+#[derive(Component)]
+pub struct ToolButtonText {
+    pub mode: ToolMode,
+}
+
+/// Cycles the kind of particle the tools spawn
+#[derive(Component)]
+pub struct ToolKindButton;
+
+#[derive(Component)]
+pub struct ToolKindText;
+
+#[derive(Component)]
+pub struct ToolCommandButton {
+    pub command: ToolCommand,
+}
+
+#[derive(Component)]
+pub struct ToolStatusText;
+
+/// Aggregate stats of the selection and operations on all of it
+#[derive(Component)]
+pub struct SelectionPanel;
+
+#[derive(Component)]
+pub struct SelectionStatsText;
+
+/// What a button in the selection panel does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionAction {
+    Delete,
+    ToggleFrozen,
+    /// Recolour the selection, or return it to the colour scheme
+    Recolour(Option<Color>),
+    SavePrefab,
+    Clear,
+}
+
+#[derive(Component)]
+pub struct SelectionButton {
+    pub action: SelectionAction,
+}
+
+#[derive(Component)]
+pub struct ConsolePanel;
+
+#[derive(Component)]
+pub struct ConsoleLogText;
+
+/// The console's input line, which holds the keyboard focus while open
+#[derive(Component)]
+pub struct ConsoleInput;
@@ -1,7 +1,9 @@
 mod components;
 mod plugins;
+mod resources;
 mod systems;
 
 pub use components::*;
 pub use plugins::*;
+pub use resources::*;
 pub use systems::*;
@@ -5,8 +5,25 @@ use bevy::{
     },
     prelude::*,
 };
+use view::keyboard_free;
 
-use crate::{setup_hud, update_hud};
+use crate::{
+    click_outliner_controls, click_parameter_actions, click_parameter_fields,
+    click_periodic_cell, click_pinned_particle_buttons, click_plot_toggles,
+    click_selection_buttons, click_sim_controls, click_toolbar,
+    drag_parameter_sliders, edit_console, edit_parameter_fields,
+    interact_outliner_rows, refresh_atom_outliner, scroll_atom_outliner,
+    setup_atom_outliner, setup_console, setup_hud, setup_parameter_panel,
+    setup_particle_inspector, setup_periodic_table, setup_plot_panel,
+    setup_selection_panel, setup_sim_controls, setup_toolbar,
+    sim_control_shortcuts, toggle_atom_outliner, toggle_console,
+    toggle_parameter_panel, toggle_periodic_table, toggle_plot_panel,
+    update_color_legend, update_console, update_element_details, update_hud,
+    update_nucleus_inspector, update_parameter_panel, update_particle_tooltip,
+    update_periodic_table, update_pinned_particle, update_plot,
+    update_selection_panel, update_sim_status, update_toolbar, Console,
+    OutlinerView, PlotView,
+};
 
 pub struct HudPlugin;
 
@@ -17,7 +34,74 @@ impl Plugin for HudPlugin {
             EntityCountDiagnosticsPlugin,
             SystemInformationDiagnosticsPlugin,
         ))
-        .add_systems(Startup, setup_hud)
-        .add_systems(Update, update_hud);
+        .init_resource::<PlotView>()
+        .init_resource::<OutlinerView>()
+        .init_resource::<Console>()
+        .add_systems(
+            Startup,
+            (
+                setup_hud,
+                setup_periodic_table,
+                setup_sim_controls,
+                setup_parameter_panel,
+                setup_plot_panel,
+                setup_atom_outliner,
+                setup_particle_inspector,
+                setup_toolbar,
+                setup_selection_panel,
+                setup_console,
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                update_hud,
+                update_element_details,
+                update_color_legend,
+                update_nucleus_inspector,
+                toggle_periodic_table.run_if(keyboard_free),
+                update_periodic_table,
+                click_periodic_cell,
+                (
+                    sim_control_shortcuts.run_if(keyboard_free),
+                    click_sim_controls,
+                    update_sim_status,
+                )
+                    .chain(),
+                (
+                    toggle_parameter_panel.run_if(keyboard_free),
+                    drag_parameter_sliders,
+                    click_parameter_fields,
+                    edit_parameter_fields,
+                    click_parameter_actions,
+                    update_parameter_panel,
+                )
+                    .chain(),
+                (
+                    toggle_plot_panel.run_if(keyboard_free),
+                    click_plot_toggles,
+                    update_plot,
+                )
+                    .chain(),
+                (
+                    toggle_atom_outliner.run_if(keyboard_free),
+                    click_outliner_controls,
+                    refresh_atom_outliner,
+                    interact_outliner_rows,
+                    scroll_atom_outliner,
+                )
+                    .chain(),
+                (
+                    update_particle_tooltip,
+                    click_pinned_particle_buttons,
+                    update_pinned_particle,
+                )
+                    .chain(),
+                (click_toolbar, update_toolbar).chain(),
+                (click_selection_buttons, update_selection_panel).chain(),
+                (toggle_console, edit_console, update_console).chain(),
+            ),
+        );
     }
 }
@@ -0,0 +1,84 @@
+use std::collections::{HashSet, VecDeque};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use mechanics::element::Element;
+
+use crate::{OutlinerSort, PlotSeries};
+
+/// Line chart of the stats history, drawn on the CPU into `image` for the
+/// plot panel to show.
+#[derive(Resource, Debug)]
+pub struct PlotView {
+    pub image: Handle<Image>,
+    /// Series drawn, each scaled to its own range
+    pub series: HashSet<PlotSeries>,
+}
+
+impl PlotView {
+    /// Size of `image` in pixels
+    pub const WIDTH: u32 = 480;
+    pub const HEIGHT: u32 = 180;
+}
+
+impl FromWorld for PlotView {
+    fn from_world(world: &mut World) -> Self {
+        let image = Image::new_fill(
+            Extent3d {
+                width: Self::WIDTH,
+                height: Self::HEIGHT,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+
+        Self {
+            image: world.resource_mut::<Assets<Image>>().add(image),
+            series: HashSet::from([
+                PlotSeries::FreeNucleons,
+                PlotSeries::Atoms,
+            ]),
+        }
+    }
+}
+
+/// Order and filter of the atom outliner.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct OutlinerView {
+    pub sort: OutlinerSort,
+    pub descending: bool,
+    /// Only list atoms of this element, all if `None`
+    pub filter: Option<Element>,
+}
+
+/// Output, input line and history of the developer console.
+#[derive(Resource, Debug, Default)]
+pub struct Console {
+    /// Printed lines, oldest first
+    pub log: VecDeque<String>,
+    pub input: String,
+    /// Submitted lines, oldest first
+    pub history: Vec<String>,
+    /// Entry of `history` shown in the input line while browsing it
+    pub browsing: Option<usize>,
+}
+
+impl Console {
+    /// Lines kept in `log`
+    pub const CAPACITY: usize = 200;
+
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.log.push_back(line.into());
+        while self.log.len() > Self::CAPACITY {
+            self.log.pop_front();
+        }
+    }
+}
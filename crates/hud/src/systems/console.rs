@@ -0,0 +1,853 @@
+use std::path::Path;
+
+use bevy::{
+    ecs::system::RunSystemOnce,
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use bevy_dynamics::Velocity;
+use mechanics::element::Element;
+use mechanics::particle::{Kind, Particle};
+use mechanics::prefab::Prefab;
+use mechanics::snapshot::Snapshot;
+use mechanics::{
+    restore_snapshot, spawn_particle, Atom, PendingSteps, PhysicsConstants,
+    PhysicsParameter, Selected, SimRng, SimStats, SimStep, SimulationState,
+    StatsHistory,
+};
+use rand::Rng;
+use strum::IntoEnumIterator;
+use view::{ParticleInspector, SelectionCommand, TextInputFocus, UndoStack};
+
+use crate::{Console, ConsoleInput, ConsoleLogText, ConsolePanel, HudCamera};
+
+/// Where `snapshot save` and `snapshot load` look for snapshots
+const SNAPSHOT_DIR: &str = "snapshots";
+/// Where `prefab load` looks for prefabs, as saved from the selection panel
+const PREFAB_DIR: &str = "prefabs";
+/// Log lines shown above the input line
+const VISIBLE_LINES: usize = 14;
+/// Radius of the sphere `spawn` scatters several particles in, so they
+/// don't start on top of each other
+const SPAWN_SPREAD: f32 = 10.0;
+/// Most particles `spawn` creates at once, as they all spawn within one
+/// frame
+const MAX_SPAWN: usize = 1_000;
+/// Most steps `step` takes at once, as they all run within one frame
+const MAX_STEPS: u32 = 10_000;
+/// Command names with their usage, as listed by `help`
+const COMMANDS: [(&str, &str); 9] = [
+    ("spawn", "spawn <kind> [count] [at x,y,z]"),
+    ("set", "set <parameter> [value]"),
+    ("select", "select element <name> | select none"),
+    ("snapshot", "snapshot save|load <name>"),
+    (
+        "prefab",
+        "prefab save <name> | prefab load <name> [at x,y,z]",
+    ),
+    ("seed", "seed <n>"),
+    ("step", "step <n>"),
+    ("help", "help"),
+    ("clear", "clear"),
+];
+
+/// A parsed console line that acts on the world
+#[derive(Debug, Clone, PartialEq)]
+enum ConsoleCommand {
+    Spawn {
+        kind: Kind,
+        count: usize,
+        position: Vec3,
+    },
+    Set {
+        parameter: PhysicsParameter,
+        /// Only print the current value if `None`
+        value: Option<f32>,
+    },
+    SelectElement(Element),
+    SelectNone,
+    SaveSnapshot(String),
+    LoadSnapshot(String),
+    SavePrefab(String),
+    LoadPrefab {
+        name: String,
+        position: Vec3,
+    },
+    Seed(u64),
+    Step(u32),
+}
+
+fn kind_name(kind: Kind) -> String {
+    format!("{kind:?}").to_lowercase()
+}
+
+fn parse_kind(token: &str) -> Result<Kind, String> {
+    Kind::iter()
+        .find(|kind| kind_name(*kind).eq_ignore_ascii_case(token))
+        .ok_or_else(|| format!("Unknown kind {token}"))
+}
+
+fn parse_element(token: &str) -> Result<Element, String> {
+    Element::iter()
+        .find(|element| element.to_string().eq_ignore_ascii_case(token))
+        .ok_or_else(|| format!("Unknown element {token}"))
+}
+
+/// The parameter whose field name is or starts with `token`, so
+/// `coulomb` is enough for `coulomb_constant`
+fn parse_parameter(token: &str) -> Result<PhysicsParameter, String> {
+    let token = token.to_lowercase();
+    let matches: Vec<PhysicsParameter> = PhysicsParameter::iter()
+        .filter(|parameter| parameter.name().starts_with(&token))
+        .collect();
+    match matches.as_slice() {
+        [parameter] => Ok(*parameter),
+        [] => Err(format!("Unknown parameter {token}")),
+        _ => Err(format!("Ambiguous parameter {token}")),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(token: &str) -> Result<T, String> {
+    token
+        .parse()
+        .map_err(|_| format!("Expected a number, got {token}"))
+}
+
+/// A number that can go into a position or a constant, so not `nan` or
+/// `inf`
+fn parse_finite(token: &str) -> Result<f32, String> {
+    match parse_number::<f32>(token)? {
+        value if value.is_finite() => Ok(value),
+        _ => Err(format!("Expected a finite number, got {token}")),
+    }
+}
+
+/// A snapshot or prefab name, which must stay inside its directory
+fn parse_file_name(token: &str) -> Result<String, String> {
+    if token.contains(['/', '\\']) || token.contains("..") {
+        return Err(format!("Invalid name {token}"));
+    }
+    Ok(token.to_string())
+}
+
+/// `x,y,z`, with or without spaces after the commas
+fn parse_position(tokens: &[&str]) -> Result<Vec3, String> {
+    let joined = tokens.concat();
+    let values = joined
+        .split(',')
+        .map(parse_finite)
+        .collect::<Result<Vec<_>, _>>()?;
+    match values.as_slice() {
+        [x, y, z] => Ok(Vec3::new(*x, *y, *z)),
+        _ => Err(format!("Expected x,y,z, got {joined}")),
+    }
+}
+
+/// Position after an optional `at`, the origin without one
+fn parse_at(tokens: &[&str]) -> Result<Vec3, String> {
+    match tokens {
+        [] => Ok(Vec3::ZERO),
+        ["at", position @ ..] => parse_position(position),
+        _ => Err(format!("Expected at x,y,z, got {}", tokens.join(" "))),
+    }
+}
+
+fn parse(line: &str) -> Result<ConsoleCommand, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+        ["spawn", kind, rest @ ..] => {
+            let kind = parse_kind(kind)?;
+            let (count, rest) = match rest {
+                [count, rest @ ..] if *count != "at" => {
+                    (parse_number(count)?, rest)
+                }
+                _ => (1, rest),
+            };
+            if count > MAX_SPAWN {
+                return Err(format!("At most {MAX_SPAWN} particles at once"));
+            }
+            Ok(ConsoleCommand::Spawn {
+                kind,
+                count,
+                position: parse_at(rest)?,
+            })
+        }
+        ["set", parameter] => Ok(ConsoleCommand::Set {
+            parameter: parse_parameter(parameter)?,
+            value: None,
+        }),
+        ["set", parameter, value] => {
+            let parameter = parse_parameter(parameter)?;
+            match parse_finite(value)? {
+                value if PhysicsParameter::accepts(value) => {
+                    Ok(ConsoleCommand::Set {
+                        parameter,
+                        value: Some(value),
+                    })
+                }
+                _ => Err(format!("{} can't be negative", parameter.name())),
+            }
+        }
+        ["select", "element", element] => {
+            Ok(ConsoleCommand::SelectElement(parse_element(element)?))
+        }
+        ["select", "none"] => Ok(ConsoleCommand::SelectNone),
+        ["snapshot", "save", name] => {
+            Ok(ConsoleCommand::SaveSnapshot(parse_file_name(name)?))
+        }
+        ["snapshot", "load", name] => {
+            Ok(ConsoleCommand::LoadSnapshot(parse_file_name(name)?))
+        }
+        ["prefab", "save", name] => {
+            Ok(ConsoleCommand::SavePrefab(parse_file_name(name)?))
+        }
+        ["prefab", "load", name, rest @ ..] => Ok(ConsoleCommand::LoadPrefab {
+            name: parse_file_name(name)?,
+            position: parse_at(rest)?,
+        }),
+        ["seed", seed] => Ok(ConsoleCommand::Seed(parse_number(seed)?)),
+        ["step", steps] => match parse_number(steps)? {
+            steps if steps > MAX_STEPS => {
+                Err(format!("At most {MAX_STEPS} steps at once"))
+            }
+            steps => Ok(ConsoleCommand::Step(steps)),
+        },
+        [name, ..] => {
+            match COMMANDS.iter().find(|(command, _)| command == name) {
+                Some((_, usage)) => Err(format!("Usage: {usage}")),
+                None => Err(format!("Unknown command {name}, try help")),
+            }
+        }
+        [] => Err("Empty command".to_string()),
+    }
+}
+
+/// Words that can follow `previous` tokens
+fn candidates(previous: &[&str]) -> Vec<String> {
+    let words =
+        |words: &[&str]| words.iter().map(ToString::to_string).collect();
+    match previous {
+        [] => COMMANDS
+            .iter()
+            .map(|(name, _)| (*name).to_string())
+            .collect(),
+        ["spawn"] => Kind::iter().map(kind_name).collect(),
+        ["spawn", _] | ["spawn", _, _] | ["prefab", "load", _] => {
+            words(&["at"])
+        }
+        ["set"] => PhysicsParameter::iter()
+            .map(|parameter| parameter.name().to_string())
+            .collect(),
+        ["select"] => words(&["element", "none"]),
+        ["select", "element"] => {
+            Element::iter().map(|element| element.to_string()).collect()
+        }
+        ["snapshot"] | ["prefab"] => words(&["save", "load"]),
+        _ => Vec::new(),
+    }
+}
+
+/// Completes the last word of `input`, returning the new input and the
+/// candidates left if the word is still ambiguous
+fn complete(input: &str) -> (String, Vec<String>) {
+    let (head, word) = input
+        .rfind(char::is_whitespace)
+        .map_or(("", input), |i| input.split_at(i + 1));
+    let previous: Vec<&str> = head.split_whitespace().collect();
+    let matches: Vec<String> = candidates(&previous)
+        .into_iter()
+        .filter(|candidate| {
+            candidate.to_lowercase().starts_with(&word.to_lowercase())
+        })
+        .collect();
+
+    match matches.as_slice() {
+        [] => (input.to_string(), Vec::new()),
+        [only] => (format!("{head}{only} "), Vec::new()),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.len(), |common, other| {
+                first
+                    .chars()
+                    .zip(other.chars())
+                    .take(common)
+                    .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                    .count()
+            });
+            let completed = if common > word.len() {
+                format!("{head}{}", &first[..common])
+            } else {
+                input.to_string()
+            };
+            (completed, matches)
+        }
+    }
+}
+
+fn spawn(
+    world: &mut World,
+    kind: Kind,
+    count: usize,
+    position: Vec3,
+) -> Result<String, String> {
+    let damping = world.resource::<PhysicsConstants>().damping;
+    let positions: Vec<Vec3> = {
+        let mut rng = world.resource_mut::<SimRng>();
+        (0..count)
+            .map(|_| {
+                if count == 1 {
+                    return position;
+                }
+                loop {
+                    let offset = Vec3::new(
+                        rng.gen_range(-1.0..=1.0),
+                        rng.gen_range(-1.0..=1.0),
+                        rng.gen_range(-1.0..=1.0),
+                    );
+                    if offset.length_squared() <= 1.0 {
+                        break position + offset * SPAWN_SPREAD;
+                    }
+                }
+            })
+            .collect()
+    };
+
+    let mut commands = world.commands();
+    for position in positions {
+        spawn_particle(&mut commands, kind, position, Vec3::ZERO, damping);
+    }
+    world.flush();
+    Ok(format!("Spawned {count} {}", kind_name(kind)))
+}
+
+fn select_element(world: &mut World, element: Option<Element>) -> String {
+    let selected: Vec<Entity> = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect();
+    for entity in selected {
+        world.entity_mut(entity).remove::<Selected>();
+    }
+
+    let Some(element) = element else {
+        return "Selection cleared".to_string();
+    };
+    let atoms: Vec<Entity> = world
+        .query::<(Entity, &Atom)>()
+        .iter(world)
+        .filter(|(_, atom)| atom.element == element)
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in &atoms {
+        world.entity_mut(*entity).insert(Selected);
+    }
+    format!("Selected {} {element} atoms", atoms.len())
+}
+
+/// Replaces every particle and atom with the contents of the snapshot, and
+/// forgets whatever referred to the old run
+fn load_snapshot(world: &mut World, path: &Path) -> Result<String, String> {
+    let snapshot = Snapshot::load(path)?;
+    let count = snapshot.particles.len();
+
+    let existing: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Particle>, With<Atom>)>>()
+        .iter(world)
+        .collect();
+    for entity in existing {
+        // Particles may already be gone with their atom
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+
+    world.resource_mut::<StatsHistory>().samples.clear();
+    world.insert_resource(SimStats::default());
+    world.resource_mut::<UndoStack>().actions.clear();
+    *world.resource_mut::<ParticleInspector>() = ParticleInspector::default();
+    let timestep = world.resource::<Time<Fixed>>().timestep();
+    world.insert_resource(Time::<Fixed>::from_duration(timestep));
+
+    world.resource_mut::<SimRng>().reseed(snapshot.seed);
+    world.insert_resource(snapshot);
+    world
+        .run_system_once(restore_snapshot)
+        .map_err(|err| format!("Failed to restore snapshot: {err}"))?;
+    Ok(format!("Loaded {count} particles from {}", path.display()))
+}
+
+fn execute(
+    world: &mut World,
+    command: ConsoleCommand,
+) -> Result<String, String> {
+    match command {
+        ConsoleCommand::Spawn {
+            kind,
+            count,
+            position,
+        } => spawn(world, kind, count, position),
+        ConsoleCommand::Set { parameter, value } => {
+            let mut physics = world.resource_mut::<PhysicsConstants>();
+            if let Some(value) = value {
+                parameter.set(&mut physics, value);
+            }
+            Ok(format!(
+                "{} = {}",
+                parameter.name(),
+                parameter.get(&physics)
+            ))
+        }
+        ConsoleCommand::SelectElement(element) => {
+            Ok(select_element(world, Some(element)))
+        }
+        ConsoleCommand::SelectNone => Ok(select_element(world, None)),
+        ConsoleCommand::SaveSnapshot(name) => {
+            let path = Path::new(SNAPSHOT_DIR).join(format!("{name}.ron"));
+            let step = world.resource::<SimStep>().0;
+            let seed = world.resource::<SimRng>().seed();
+            let physics = *world.resource::<PhysicsConstants>();
+            let snapshot = Snapshot::capture(
+                step,
+                seed,
+                physics,
+                world
+                    .query::<(&Particle, &Transform, &Velocity)>()
+                    .iter(world),
+            );
+            snapshot.save(&path)?;
+            Ok(format!(
+                "Saved {} particles to {}",
+                snapshot.particles.len(),
+                path.display()
+            ))
+        }
+        ConsoleCommand::LoadSnapshot(name) => load_snapshot(
+            world,
+            &Path::new(SNAPSHOT_DIR).join(format!("{name}.ron")),
+        ),
+        ConsoleCommand::SavePrefab(name) => {
+            world.send_event(SelectionCommand::SavePrefab(name.clone()));
+            Ok(format!("Saving the selection to {PREFAB_DIR}/{name}.ron"))
+        }
+        ConsoleCommand::LoadPrefab { name, position } => {
+            let path = Path::new(PREFAB_DIR).join(format!("{name}.ron"));
+            let prefab = Prefab::load(&path)?;
+            let damping = world.resource::<PhysicsConstants>().damping;
+            let mut commands = world.commands();
+            let spawned = prefab.spawn(&mut commands, position, damping);
+            world.flush();
+            Ok(format!("Spawned {} particles from {name}", spawned.len()))
+        }
+        ConsoleCommand::Seed(seed) => {
+            world.resource_mut::<SimRng>().reseed(seed);
+            Ok(format!("Reseeded with {seed}"))
+        }
+        ConsoleCommand::Step(steps) => {
+            // Paused right away, so the fixed loop adds no steps of its own
+            world.resource_mut::<Time<Virtual>>().pause();
+            world
+                .resource_mut::<NextState<SimulationState>>()
+                .set(SimulationState::Paused);
+            let mut pending = world.resource_mut::<PendingSteps>();
+            pending.0 = pending.0.saturating_add(steps);
+            Ok(format!("Stepping {steps} times"))
+        }
+    }
+}
+
+pub fn setup_console(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 13.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            // Below the toolbar
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(60.0),
+                left: Val::Percent(25.0),
+                width: Val::Percent(50.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(6.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(Color::srgba_u8(30, 30, 46, 230)),
+            Visibility::Hidden,
+            ConsolePanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                font.clone(),
+                TextColor(Color::srgb_u8(166, 173, 200)),
+                ConsoleLogText,
+            ));
+            parent.spawn((
+                Text::default(),
+                font.clone(),
+                TextColor(Color::srgb_u8(205, 214, 244)),
+                ConsoleInput,
+            ));
+        });
+}
+
+// system that opens and closes the console on pressing `
+pub fn toggle_console(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    input: Query<Entity, With<ConsoleInput>>,
+    mut focus: ResMut<TextInputFocus>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Backquote) {
+        return;
+    }
+    let Ok(input) = input.get_single() else {
+        return;
+    };
+    // Not while typing into another field
+    if focus.0 == Some(input) {
+        focus.0 = None;
+    } else if focus.0.is_none() {
+        focus.0 = Some(input);
+    }
+}
+
+/// Types into the console while it is open. Enter runs the line, Tab
+/// completes the word under the cursor and Up and Down browse the history.
+pub fn edit_console(
+    mut commands: Commands,
+    mut events: EventReader<KeyboardInput>,
+    mut console: ResMut<Console>,
+    mut focus: ResMut<TextInputFocus>,
+    input: Query<Entity, With<ConsoleInput>>,
+) {
+    let Ok(input) = input.get_single() else {
+        return;
+    };
+    if focus.0 != Some(input) {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            // Left to `toggle_console`
+            Key::Character(text) if text.as_str() == "`" => {}
+            Key::Character(text) => console.input.push_str(text),
+            Key::Space => console.input.push(' '),
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Tab => {
+                let (completed, matches) = complete(&console.input);
+                console.input = completed;
+                if !matches.is_empty() {
+                    console.print(matches.join("  "));
+                }
+            }
+            Key::ArrowUp => {
+                let index = match console.browsing {
+                    Some(index) => index.saturating_sub(1),
+                    None => console.history.len().saturating_sub(1),
+                };
+                if let Some(line) = console.history.get(index).cloned() {
+                    console.input = line;
+                    console.browsing = Some(index);
+                }
+            }
+            Key::ArrowDown => {
+                let next = console.browsing.map(|index| index + 1);
+                match next.and_then(|index| console.history.get(index)) {
+                    Some(line) => {
+                        console.input = line.clone();
+                        console.browsing = next;
+                    }
+                    None => {
+                        console.input.clear();
+                        console.browsing = None;
+                    }
+                }
+            }
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                let line = line.trim();
+                console.browsing = None;
+                if line.is_empty() {
+                    continue;
+                }
+                console.print(format!("> {line}"));
+                if console.history.last().map(String::as_str) != Some(line) {
+                    console.history.push(line.to_string());
+                }
+
+                match line {
+                    "help" => {
+                        for (_, usage) in COMMANDS {
+                            console.print(usage);
+                        }
+                    }
+                    "clear" => console.log.clear(),
+                    _ => match parse(line) {
+                        // Runs once the system is done, with the whole world
+                        Ok(command) => {
+                            commands.queue(move |world: &mut World| {
+                                let output = execute(world, command)
+                                    .unwrap_or_else(|err| {
+                                        format!("error: {err}")
+                                    });
+                                world.resource_mut::<Console>().print(output);
+                            })
+                        }
+                        Err(err) => console.print(format!("error: {err}")),
+                    },
+                }
+            }
+            Key::Escape => focus.0 = None,
+            _ => {}
+        }
+    }
+}
+
+/// Shows the console while its input line has the focus
+pub fn update_console(
+    console: Res<Console>,
+    focus: Res<TextInputFocus>,
+    mut panel: Query<&mut Visibility, With<ConsolePanel>>,
+    mut log: Query<&mut Text, With<ConsoleLogText>>,
+    mut input: Query<
+        (Entity, &mut Text),
+        (With<ConsoleInput>, Without<ConsoleLogText>),
+    >,
+) {
+    let Ok(mut visibility) = panel.get_single_mut() else {
+        return;
+    };
+    let Ok((input, mut input_text)) = input.get_single_mut() else {
+        return;
+    };
+
+    let open = focus.0 == Some(input);
+    visibility.set_if_neq(if open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    // Refreshed on opening too, for lines printed while closed
+    if !open || !(console.is_changed() || focus.is_changed()) {
+        return;
+    }
+
+    if let Ok(mut text) = log.get_single_mut() {
+        let skip = console.log.len().saturating_sub(VISIBLE_LINES);
+        text.0 = console
+            .log
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    input_text.0 = format!("> {}_", console.input);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec3;
+    use mechanics::particle::Kind;
+    use mechanics::PhysicsParameter;
+
+    use super::{complete, parse, ConsoleCommand, MAX_SPAWN, MAX_STEPS};
+
+    #[test]
+    fn parses_spawn_with_and_without_count_and_position() {
+        assert_eq!(
+            parse("spawn proton 10 at 0,0,0"),
+            Ok(ConsoleCommand::Spawn {
+                kind: Kind::Proton,
+                count: 10,
+                position: Vec3::ZERO,
+            })
+        );
+        assert_eq!(
+            parse("spawn Electron at 1, -2, 3.5"),
+            Ok(ConsoleCommand::Spawn {
+                kind: Kind::Electron,
+                count: 1,
+                position: Vec3::new(1.0, -2.0, 3.5),
+            })
+        );
+        assert_eq!(
+            parse("spawn neutron 3"),
+            Ok(ConsoleCommand::Spawn {
+                kind: Kind::Neutron,
+                count: 3,
+                position: Vec3::ZERO,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_positions() {
+        assert_eq!(
+            parse("spawn proton at 1,2"),
+            Err("Expected x,y,z, got 1,2".to_string())
+        );
+        assert_eq!(
+            parse("spawn proton 2 near 1,2,3"),
+            Err("Expected at x,y,z, got near 1,2,3".to_string())
+        );
+        assert_eq!(
+            parse("prefab load ring at 1,a,3"),
+            Err("Expected a number, got a".to_string())
+        );
+        assert_eq!(
+            parse("spawn photon at 0,inf,0"),
+            Err("Expected a finite number, got inf".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_parameters_by_prefix() {
+        assert_eq!(
+            parse("set coulomb 50000"),
+            Ok(ConsoleCommand::Set {
+                parameter: PhysicsParameter::CoulombConstant,
+                value: Some(50000.0),
+            })
+        );
+        assert_eq!(
+            parse("set DAMP"),
+            Ok(ConsoleCommand::Set {
+                parameter: PhysicsParameter::Damping,
+                value: None,
+            })
+        );
+        assert_eq!(
+            parse("set magnetism 1"),
+            Err("Unknown parameter magnetism".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_values_the_parameter_panel_rejects() {
+        assert_eq!(
+            parse("set coulomb nan"),
+            Err("Expected a finite number, got nan".to_string())
+        );
+        assert_eq!(
+            parse("set gravitational -inf"),
+            Err("Expected a finite number, got -inf".to_string())
+        );
+        assert_eq!(
+            parse("set damping -0.5"),
+            Err("damping can't be negative".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_names_inside_their_directory() {
+        assert_eq!(
+            parse("snapshot save ../../foo"),
+            Err("Invalid name ../../foo".to_string())
+        );
+        assert_eq!(
+            parse("prefab load nested\\ring"),
+            Err("Invalid name nested\\ring".to_string())
+        );
+        assert_eq!(
+            parse("snapshot load run-2"),
+            Ok(ConsoleCommand::LoadSnapshot("run-2".to_string()))
+        );
+    }
+
+    #[test]
+    fn caps_spawn_count() {
+        assert_eq!(
+            parse(&format!("spawn proton {MAX_SPAWN}")),
+            Ok(ConsoleCommand::Spawn {
+                kind: Kind::Proton,
+                count: MAX_SPAWN,
+                position: Vec3::ZERO,
+            })
+        );
+        assert_eq!(
+            parse(&format!("spawn proton {}", MAX_SPAWN + 1)),
+            Err(format!("At most {MAX_SPAWN} particles at once"))
+        );
+    }
+
+    #[test]
+    fn caps_steps() {
+        assert_eq!(
+            parse(&format!("step {MAX_STEPS}")),
+            Ok(ConsoleCommand::Step(MAX_STEPS))
+        );
+        assert_eq!(
+            parse(&format!("step {}", MAX_STEPS + 1)),
+            Err(format!("At most {MAX_STEPS} steps at once"))
+        );
+    }
+
+    #[test]
+    fn explains_usage_and_unknown_commands() {
+        assert_eq!(parse("seed"), Err("Usage: seed <n>".to_string()));
+        assert_eq!(
+            parse("teleport"),
+            Err("Unknown command teleport, try help".to_string())
+        );
+    }
+
+    #[test]
+    fn completes_a_unique_match() {
+        assert_eq!(complete("sn"), ("snapshot ".to_string(), Vec::new()));
+        assert_eq!(
+            complete("spawn pro"),
+            ("spawn proton ".to_string(), Vec::new())
+        );
+        assert_eq!(
+            complete("select element car"),
+            ("select element Carbon ".to_string(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn completes_the_common_prefix_of_ambiguous_matches() {
+        let (input, matches) = complete("se");
+        assert_eq!(input, "se");
+        assert_eq!(matches, ["set", "select", "seed"]);
+
+        let (input, matches) = complete("select element rut");
+        assert_eq!(input, "select element Ruthe");
+        assert_eq!(matches, ["Ruthenium", "Rutherfordium"]);
+
+        // Nothing in common beyond what was typed
+        let (input, matches) = complete("snapshot ");
+        assert_eq!(input, "snapshot ");
+        assert_eq!(matches, ["save", "load"]);
+    }
+
+    #[test]
+    fn leaves_input_without_matches_alone() {
+        assert_eq!(complete("warp"), ("warp".to_string(), Vec::new()));
+        assert_eq!(complete("seed 4"), ("seed 4".to_string(), Vec::new()));
+    }
+}
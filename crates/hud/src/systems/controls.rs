@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+use mechanics::{PendingSteps, SimStep, SimulationState, TimeScale};
+
+use crate::{HudCamera, SimControl, SimControlButton, SimStatusText};
+
+/// Steps taken by the step-N button and Shift + Right
+const STEP_N: u32 = 10;
+const SPEEDS: [f32; 6] = [0.1, 0.25, 0.5, 1.0, 2.0, 4.0];
+// Surface0 and Surface1
+pub(super) const BUTTON_COLOR: Color = Color::srgba(0.192, 0.196, 0.267, 0.9);
+pub(super) const BUTTON_HOVER_COLOR: Color =
+    Color::srgba(0.271, 0.278, 0.353, 0.9);
+
+pub fn setup_sim_controls(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 14.0,
+        ..default()
+    };
+
+    let buttons = [
+        ("Play/Pause", SimControl::TogglePause),
+        ("Step", SimControl::Step(1)),
+        ("Step 10", SimControl::Step(STEP_N)),
+    ]
+    .into_iter()
+    .map(|(label, control)| (label.to_string(), control))
+    .chain(
+        SPEEDS
+            .iter()
+            .map(|speed| (format!("{speed}x"), SimControl::Speed(*speed))),
+    );
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            // Full width only to centre the strip, so it must not swallow
+            // clicks meant for the scene
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(6.0)),
+                        column_gap: Val::Px(6.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+                ))
+                .with_children(|parent| {
+                    for (label, control) in buttons {
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(
+                                        Val::Px(8.0),
+                                        Val::Px(4.0),
+                                    ),
+                                    ..default()
+                                },
+                                BorderRadius::all(Val::Px(6.0)),
+                                BackgroundColor(BUTTON_COLOR),
+                                SimControlButton { control },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(label),
+                                    font.clone(),
+                                    TextColor(Color::srgb_u8(205, 214, 244)),
+                                ));
+                            });
+                    }
+
+                    parent.spawn((
+                        Text::default(),
+                        font.clone(),
+                        TextColor(Color::srgb_u8(180, 190, 254)),
+                        Node {
+                            margin: UiRect::left(Val::Px(6.0)),
+                            ..default()
+                        },
+                        SimStatusText,
+                    ));
+                });
+        });
+}
+
+fn apply_control(
+    control: SimControl,
+    state: &State<SimulationState>,
+    next_state: &mut NextState<SimulationState>,
+    scale: &mut TimeScale,
+    pending: &mut PendingSteps,
+    time: &mut Time<Virtual>,
+) {
+    match control {
+        SimControl::TogglePause => next_state.set(match state.get() {
+            SimulationState::Running => SimulationState::Paused,
+            SimulationState::Paused => SimulationState::Running,
+        }),
+        SimControl::Step(steps) => {
+            // Paused right away rather than on the state change next frame,
+            // so the fixed loop doesn't run its own steps on top
+            time.pause();
+            next_state.set(SimulationState::Paused);
+            pending.0 = pending.0.saturating_add(steps);
+        }
+        SimControl::Speed(speed) => scale.0 = speed,
+    }
+}
+
+pub fn click_sim_controls(
+    mut buttons: Query<
+        (&Interaction, &SimControlButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    state: Res<State<SimulationState>>,
+    mut next_state: ResMut<NextState<SimulationState>>,
+    mut scale: ResMut<TimeScale>,
+    mut pending: ResMut<PendingSteps>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                apply_control(
+                    button.control,
+                    &state,
+                    &mut next_state,
+                    &mut scale,
+                    &mut pending,
+                    &mut time,
+                );
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+// system that toggles pause on pressing Space, steps once on pressing Right
+// or ten times with Shift + Right, and changes speed with [ and ]
+pub fn sim_control_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<SimulationState>>,
+    mut next_state: ResMut<NextState<SimulationState>>,
+    mut scale: ResMut<TimeScale>,
+    mut pending: ResMut<PendingSteps>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let shift = keyboard_input.pressed(KeyCode::ShiftLeft)
+        || keyboard_input.pressed(KeyCode::ShiftRight);
+    let current = SPEEDS
+        .iter()
+        .position(|speed| *speed >= scale.0)
+        .unwrap_or(SPEEDS.len() - 1);
+
+    let control = if keyboard_input.just_pressed(KeyCode::Space) {
+        SimControl::TogglePause
+    } else if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        SimControl::Step(if shift { STEP_N } else { 1 })
+    } else if keyboard_input.just_pressed(KeyCode::BracketLeft) {
+        SimControl::Speed(SPEEDS[current.saturating_sub(1)])
+    } else if keyboard_input.just_pressed(KeyCode::BracketRight) {
+        SimControl::Speed(SPEEDS[(current + 1).min(SPEEDS.len() - 1)])
+    } else {
+        return;
+    };
+    apply_control(
+        control,
+        &state,
+        &mut next_state,
+        &mut scale,
+        &mut pending,
+        &mut time,
+    );
+}
+
+pub fn update_sim_status(
+    mut text: Query<&mut Text, With<SimStatusText>>,
+    state: Res<State<SimulationState>>,
+    scale: Res<TimeScale>,
+    step: Res<SimStep>,
+) {
+    if !state.is_changed() && !scale.is_changed() && !step.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = text.get_single_mut() {
+        text.0 = format!("{:?} | step {} | {}x", state.get(), step.0, scale.0);
+    }
+}
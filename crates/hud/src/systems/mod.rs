@@ -0,0 +1,567 @@
+mod console;
+mod controls;
+mod outliner;
+mod parameters;
+mod particle_inspector;
+mod periodic_table;
+mod plots;
+mod selection;
+mod tools;
+
+pub use console::*;
+pub use controls::*;
+pub use outliner::*;
+pub use parameters::*;
+pub use particle_inspector::*;
+pub use periodic_table::*;
+pub use plots::*;
+pub use selection::*;
+pub use tools::*;
+
+use bevy::prelude::*;
+use iyes_perf_ui::prelude::PerfUiAllEntries;
+use mechanics::{Atom, Kind, Selected};
+use view::{ColorScheme, InspectorCamera, NucleusInspector, NucleusProxy};
+
+use crate::{
+    AtomicMassText, AtomicNumberText, ChargeText, ColorLegend,
+    ConstituentsText, ElementCard, ElementElectronsText, ElementNameText,
+    ElementNeutronsText, ElementSymbolText, HudCamera, IsotopeText,
+    MassNumberText, NucleusInspectorPanel, NucleusLabel,
+};
+
+/// Side length of the nucleus inspector panel in pixels
+const INSPECTOR_PANEL_SIZE: f32 = 300.0;
+
+pub fn setup_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    inspector: Res<NucleusInspector>,
+) {
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let jbm_bold: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Bold.ttf");
+
+    let hud_camera = commands
+        .spawn((
+            Camera2d,
+            Camera {
+                order: 1,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            HudCamera,
+        ))
+        .id();
+
+    commands.spawn((TargetCamera(hud_camera), PerfUiAllEntries::default()));
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    // Grows downwards with wrapped names instead of shrinking
+                    // the text to fit
+                    Node {
+                        width: Val::Px(275.0),
+                        min_height: Val::Px(275.0),
+                        border: UiRect::all(Val::Px(10.0)),
+                        padding: UiRect::axes(Val::Px(15.0), Val::Px(10.0)),
+                        row_gap: Val::Px(6.0),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::SpaceBetween,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(30.0)),
+                    BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+                    Visibility::Hidden,
+                    ElementCard,
+                ))
+                .with_children(|parent| {
+                    parent
+                        .spawn(Node {
+                            width: Val::Percent(100.0),
+                            flex_direction: FlexDirection::Row,
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::default(),
+                                TextFont {
+                                    font: jbm_regular.clone(),
+                                    font_size: 36.0,
+                                    ..Default::default()
+                                },
+                                TextColor(Color::srgb_u8(180, 190, 254)),
+                                AtomicNumberText,
+                            ));
+
+                            parent.spawn((
+                                Text::default(),
+                                TextFont {
+                                    font: jbm_regular.clone(),
+                                    font_size: 36.0,
+                                    ..Default::default()
+                                },
+                                TextColor(Color::srgb_u8(180, 190, 254)),
+                                AtomicMassText,
+                            ));
+                        });
+
+                    parent
+                        .spawn(Node {
+                            width: Val::Percent(100.0),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::default(),
+                                TextFont {
+                                    font: jbm_bold.clone(),
+                                    font_size: 96.0,
+                                    ..Default::default()
+                                },
+                                TextColor(Color::srgb_u8(180, 190, 254)),
+                                ElementSymbolText,
+                            ));
+                        });
+
+                    parent
+                        .spawn(Node {
+                            width: Val::Percent(100.0),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::default(),
+                                TextFont {
+                                    font: jbm_regular.clone(),
+                                    font_size: 32.0,
+                                    ..Default::default()
+                                },
+                                TextLayout::new(
+                                    JustifyText::Center,
+                                    LineBreak::WordOrCharacter,
+                                ),
+                                Node {
+                                    max_width: Val::Percent(100.0),
+                                    justify_self: JustifySelf::Center,
+                                    ..default()
+                                },
+                                TextColor(Color::srgb_u8(180, 190, 254)),
+                                ElementNameText,
+                            ));
+                        });
+
+                    parent
+                        .spawn(Node {
+                            width: Val::Percent(100.0),
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            spawn_detail_row(
+                                parent,
+                                &jbm_regular,
+                                "Isotope",
+                                IsotopeText,
+                            );
+                            spawn_detail_row(
+                                parent,
+                                &jbm_regular,
+                                "Mass number",
+                                MassNumberText,
+                            );
+                            spawn_detail_row(
+                                parent,
+                                &jbm_regular,
+                                "Neutrons",
+                                ElementNeutronsText,
+                            );
+                            spawn_detail_row(
+                                parent,
+                                &jbm_regular,
+                                "Electrons",
+                                ElementElectronsText,
+                            );
+                            spawn_detail_row(
+                                parent,
+                                &jbm_regular,
+                                "Charge",
+                                ChargeText,
+                            );
+                            spawn_detail_row(
+                                parent,
+                                &jbm_regular,
+                                "Constituents",
+                                ConstituentsText,
+                            );
+                        });
+                });
+
+            parent.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    row_gap: Val::Px(4.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(10.0)),
+                BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+                ColorLegend,
+            ));
+
+            parent.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    width: Val::Px(INSPECTOR_PANEL_SIZE),
+                    height: Val::Px(INSPECTOR_PANEL_SIZE),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(10.0)),
+                BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+                ImageNode::new(inspector.image.clone()),
+                Visibility::Hidden,
+                NucleusInspectorPanel,
+            ));
+        });
+}
+
+pub fn update_color_legend(
+    mut commands: Commands,
+    legend: Query<Entity, With<ColorLegend>>,
+    scheme: Res<ColorScheme>,
+    asset_server: Res<AssetServer>,
+) {
+    if !scheme.is_changed() {
+        return;
+    }
+    let Ok(legend) = legend.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 14.0,
+        ..default()
+    };
+
+    commands
+        .entity(legend)
+        .despawn_descendants()
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(scheme.mode.to_string()),
+                font.clone(),
+                TextColor(Color::srgb_u8(180, 190, 254)),
+            ));
+            for (label, color) in &scheme.legend {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(8.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Node {
+                                width: Val::Px(12.0),
+                                height: Val::Px(12.0),
+                                ..default()
+                            },
+                            BorderRadius::all(Val::Px(6.0)),
+                            BackgroundColor(*color),
+                        ));
+                        parent.spawn((
+                            Text::new(label.clone()),
+                            font.clone(),
+                            TextColor(Color::srgb_u8(205, 214, 244)),
+                        ));
+                    });
+            }
+        });
+}
+
+/// A label on the left and a value text tagged with `marker` on the right
+fn spawn_detail_row(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: &str,
+    marker: impl Component,
+) {
+    parent
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::SpaceBetween,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(166, 173, 200)),
+            ));
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(205, 214, 244)),
+                marker,
+            ));
+        });
+}
+
+pub fn update_hud(
+    mut query_element_name_text: Query<
+        &mut Text,
+        (
+            With<ElementNameText>,
+            Without<ElementSymbolText>,
+            Without<AtomicNumberText>,
+            Without<AtomicMassText>,
+            Without<ElementNeutronsText>,
+            Without<ElementElectronsText>,
+        ),
+    >,
+    mut query_atomic_number_text: Query<
+        &mut Text,
+        (
+            With<AtomicNumberText>,
+            Without<ElementSymbolText>,
+            Without<ElementNameText>,
+            Without<AtomicMassText>,
+            Without<ElementNeutronsText>,
+            Without<ElementElectronsText>,
+        ),
+    >,
+    mut query_atomic_mass_text: Query<
+        &mut Text,
+        (
+            With<AtomicMassText>,
+            Without<ElementSymbolText>,
+            Without<ElementNameText>,
+            Without<AtomicNumberText>,
+            Without<ElementNeutronsText>,
+            Without<ElementElectronsText>,
+        ),
+    >,
+    mut query_element_symbol_text: Query<
+        (&mut Text, &mut TextColor),
+        (
+            With<ElementSymbolText>,
+            Without<ElementNameText>,
+            Without<AtomicNumberText>,
+            Without<AtomicMassText>,
+            Without<ElementNeutronsText>,
+            Without<ElementElectronsText>,
+        ),
+    >,
+    mut query_element_card: Query<&mut Visibility, With<ElementCard>>,
+    query_atoms: Query<&Atom, With<Selected>>,
+) {
+    let Ok(mut element_card_visibility) = query_element_card.get_single_mut()
+    else {
+        return;
+    };
+
+    let Some(atom) = query_atoms.iter().next() else {
+        *element_card_visibility = Visibility::Hidden;
+        return;
+    };
+
+    *element_card_visibility = Visibility::Inherited;
+
+    if let Ok(mut text) = query_element_name_text.get_single_mut() {
+        text.0 = format!("{}", atom.element);
+    };
+
+    if let Ok(mut text) = query_atomic_number_text.get_single_mut() {
+        text.0 = format!("{}", atom.proton_count);
+    };
+
+    if let Ok(mut text) = query_atomic_mass_text.get_single_mut() {
+        text.0 = format!("{:3.2}", atom.mass());
+    };
+
+    if let Ok((mut text, mut color)) =
+        query_element_symbol_text.get_single_mut()
+    {
+        text.0 = format!("{}", atom.element.symbol());
+        color.0 = atom.element.color();
+    };
+}
+
+pub fn update_element_details(
+    mut texts: ParamSet<(
+        Query<&mut Text, With<IsotopeText>>,
+        Query<&mut Text, With<MassNumberText>>,
+        Query<&mut Text, With<ElementNeutronsText>>,
+        Query<&mut Text, With<ElementElectronsText>>,
+        Query<&mut Text, With<ChargeText>>,
+        Query<&mut Text, With<ConstituentsText>>,
+    )>,
+    query_atoms: Query<&Atom, With<Selected>>,
+) {
+    let Some(atom) = query_atoms.iter().next() else {
+        return;
+    };
+
+    let mass_number = atom.count();
+    if let Ok(mut text) = texts.p0().get_single_mut() {
+        // Standard ᴬ_Z X notation
+        text.0 = format!(
+            "{}{}{}",
+            superscript(mass_number),
+            subscript(atom.proton_count),
+            atom.element.symbol()
+        );
+    }
+    if let Ok(mut text) = texts.p1().get_single_mut() {
+        text.0 = mass_number.to_string();
+    }
+    if let Ok(mut text) = texts.p2().get_single_mut() {
+        text.0 = atom.neutron_count.to_string();
+    }
+    if let Ok(mut text) = texts.p3().get_single_mut() {
+        text.0 = atom.electron_count.to_string();
+    }
+    if let Ok(mut text) = texts.p4().get_single_mut() {
+        let charge =
+            i64::from(atom.proton_count) - i64::from(atom.electron_count);
+        text.0 = match charge {
+            0 => "0".to_string(),
+            charge => format!("{charge:+}e"),
+        };
+    }
+    if let Ok(mut text) = texts.p5().get_single_mut() {
+        text.0 = atom.constituent_particles.len().to_string();
+    }
+}
+
+fn superscript(value: u32) -> String {
+    const DIGITS: [char; 10] =
+        ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    map_digits(value, DIGITS)
+}
+
+fn subscript(value: u32) -> String {
+    const DIGITS: [char; 10] =
+        ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+    map_digits(value, DIGITS)
+}
+
+fn map_digits(value: u32, digits: [char; 10]) -> String {
+    value
+        .to_string()
+        .chars()
+        .filter_map(|digit| Some(digits[digit.to_digit(10)? as usize]))
+        .collect()
+}
+
+/// Shows the inspector panel while an atom is inspected, with a label over
+/// each nucleon projected through the inspector camera
+pub fn update_nucleus_inspector(
+    mut commands: Commands,
+    inspector: Res<NucleusInspector>,
+    mut panel: Query<(Entity, &mut Visibility), With<NucleusInspectorPanel>>,
+    camera: Query<(&Camera, &GlobalTransform), With<InspectorCamera>>,
+    proxies: Query<(Entity, &NucleusProxy, &GlobalTransform)>,
+    mut labels: Query<(Entity, &NucleusLabel, &mut Node)>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok((panel, mut visibility)) = panel.get_single_mut() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    *visibility = if inspector.atom.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    // The panel shows the inspector image scaled down to its size
+    let scale = INSPECTOR_PANEL_SIZE / NucleusInspector::RESOLUTION as f32;
+    let project = |position: Vec3| {
+        camera
+            .world_to_viewport(camera_transform, position)
+            .ok()
+            .map(|position| position * scale)
+    };
+
+    let mut labelled = Vec::new();
+    for (entity, label, mut node) in labels.iter_mut() {
+        let Ok((_, _, transform)) = proxies.get(label.proxy) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        labelled.push(label.proxy);
+        if let Some(position) = project(transform.translation()) {
+            node.left = Val::Px(position.x - 4.0);
+            node.top = Val::Px(position.y - 8.0);
+        }
+    }
+
+    let jbm_bold: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Bold.ttf");
+    for (proxy_entity, proxy, transform) in proxies.iter() {
+        if labelled.contains(&proxy_entity) {
+            continue;
+        }
+        let position = project(transform.translation()).unwrap_or_default();
+        let text = match proxy.kind {
+            Kind::Proton => "p",
+            Kind::Neutron => "n",
+            Kind::Electron => "e",
+            Kind::Photon => "γ",
+        };
+        let label = commands
+            .spawn((
+                Text::new(text),
+                TextFont {
+                    font: jbm_bold.clone(),
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb_u8(30, 30, 46)),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(position.x - 4.0),
+                    top: Val::Px(position.y - 8.0),
+                    ..default()
+                },
+                NucleusLabel {
+                    proxy: proxy_entity,
+                },
+            ))
+            .id();
+        commands.entity(panel).add_child(label);
+    }
+}
@@ -0,0 +1,467 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::RelativeCursorPosition,
+};
+use mechanics::{Atom, FormedAt, Selected, SimStats, SimStep};
+use view::{toggle_selection, Highlighted};
+
+use super::controls::{BUTTON_COLOR, BUTTON_HOVER_COLOR};
+use super::periodic_table::PANEL_WIDTH as PERIODIC_TABLE_WIDTH;
+use super::superscript;
+use crate::{
+    AtomOutliner, HudCamera, OutlinerFilterButton, OutlinerFilterText,
+    OutlinerList, OutlinerRow, OutlinerRowText, OutlinerSort,
+    OutlinerSortButton, OutlinerSummaryText, OutlinerView,
+};
+
+/// Seconds between refreshes, positions change every frame but nobody can
+/// read a list that fast
+const REFRESH_INTERVAL: f32 = 0.25;
+/// Pixels scrolled per line of mouse wheel
+const SCROLL_LINE_HEIGHT: f32 = 20.0;
+const LIST_HEIGHT: f32 = 400.0;
+// Surface2
+const SELECTED_ROW_COLOR: Color = Color::srgba(0.345, 0.357, 0.439, 0.8);
+
+/// One atom as listed, `key` ordering it by the current sort
+struct OutlinerEntry {
+    atom: Entity,
+    key: i64,
+    line: String,
+}
+
+fn header_line() -> String {
+    format!(
+        "{:<12} {:<7} {:>3} {:>4} {:>8} {:>6} {:>6} {:>6}",
+        "Element", "Isotope", "A", "Chg", "Age", "x", "y", "z"
+    )
+}
+
+fn atom_line(atom: &Atom, age: f32, position: Vec3) -> String {
+    let name = atom.element.to_string();
+    let isotope =
+        format!("{}{}", superscript(atom.count()), atom.element.symbol());
+    let charge = i64::from(atom.proton_count) - i64::from(atom.electron_count);
+    format!(
+        "{:<12.12} {:<7} {:>3} {:>+4} {:>7.1}s {:>6.0} {:>6.0} {:>6.0}",
+        name,
+        isotope,
+        atom.count(),
+        charge,
+        age,
+        position.x,
+        position.y,
+        position.z
+    )
+}
+
+pub fn setup_atom_outliner(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 12.0,
+        ..default()
+    };
+    let text_color = TextColor(Color::srgb_u8(205, 214, 244));
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                // Left of the periodic table, so both can be open at once
+                right: Val::Px(PERIODIC_TABLE_WIDTH + 20.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                row_gap: Val::Px(6.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+            Visibility::Hidden,
+            AtomOutliner,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(4.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(
+                                    Val::Px(6.0),
+                                    Val::Px(2.0),
+                                ),
+                                ..default()
+                            },
+                            BorderRadius::all(Val::Px(4.0)),
+                            BackgroundColor(BUTTON_COLOR),
+                            OutlinerFilterButton,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new("All elements"),
+                                font.clone(),
+                                text_color,
+                                OutlinerFilterText,
+                            ));
+                        });
+
+                    parent.spawn((
+                        Text::new("Sort"),
+                        font.clone(),
+                        TextColor(Color::srgb_u8(166, 173, 200)),
+                        Node {
+                            margin: UiRect::left(Val::Px(8.0)),
+                            ..default()
+                        },
+                    ));
+                    for sort in OutlinerSort::ALL {
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(
+                                        Val::Px(6.0),
+                                        Val::Px(2.0),
+                                    ),
+                                    ..default()
+                                },
+                                BorderRadius::all(Val::Px(4.0)),
+                                BackgroundColor(BUTTON_COLOR),
+                                OutlinerSortButton { sort },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(sort.label()),
+                                    font.clone(),
+                                    text_color,
+                                ));
+                            });
+                    }
+
+                    parent.spawn((
+                        Text::default(),
+                        font.clone(),
+                        TextColor(Color::srgb_u8(180, 190, 254)),
+                        Node {
+                            margin: UiRect::left(Val::Px(8.0)),
+                            ..default()
+                        },
+                        OutlinerSummaryText,
+                    ));
+                });
+
+            parent.spawn((
+                Text::new(header_line()),
+                font.clone(),
+                TextColor(Color::srgb_u8(166, 173, 200)),
+                Node {
+                    padding: UiRect::horizontal(Val::Px(6.0)),
+                    ..default()
+                },
+            ));
+
+            parent.spawn((
+                Node {
+                    max_height: Val::Px(LIST_HEIGHT),
+                    flex_direction: FlexDirection::Column,
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                RelativeCursorPosition::default(),
+                OutlinerList,
+            ));
+        });
+}
+
+// system that toggles the atom outliner on pressing Tab
+pub fn toggle_atom_outliner(
+    mut panel: Query<&mut Visibility, With<AtomOutliner>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        for mut visibility in panel.iter_mut() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+/// Sort buttons pick the column, or flip the order of the current one, and
+/// the filter button steps through the elements that currently exist
+pub fn click_outliner_controls(
+    mut sort_buttons: Query<
+        (&Interaction, &OutlinerSortButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut filter_buttons: Query<
+        (&Interaction, &mut BackgroundColor),
+        (
+            With<OutlinerFilterButton>,
+            Without<OutlinerSortButton>,
+            Changed<Interaction>,
+        ),
+    >,
+    mut filter_text: Query<&mut Text, With<OutlinerFilterText>>,
+    mut view: ResMut<OutlinerView>,
+    stats: Res<SimStats>,
+) {
+    for (interaction, button, mut background) in sort_buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                if view.sort == button.sort {
+                    view.descending = !view.descending;
+                } else {
+                    view.sort = button.sort;
+                    view.descending = false;
+                }
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+
+    for (interaction, mut background) in filter_buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                let mut present = stats.element_counts.keys().copied();
+                view.filter = match view.filter {
+                    None => present.next(),
+                    Some(current) => present.find(|element| *element > current),
+                };
+                if let Ok(mut text) = filter_text.get_single_mut() {
+                    text.0 = view.filter.map_or_else(
+                        || "All elements".to_string(),
+                        |element| element.to_string(),
+                    );
+                }
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+/// Lists the atoms passing the filter in sorted order, reusing the rows
+/// already spawned and only adding or removing the difference
+pub fn refresh_atom_outliner(
+    mut commands: Commands,
+    panel: Query<&Visibility, With<AtomOutliner>>,
+    list: Query<(Entity, Option<&Children>), With<OutlinerList>>,
+    mut rows: Query<(&mut OutlinerRow, &Children)>,
+    mut texts: Query<&mut Text, With<OutlinerRowText>>,
+    mut summary: Query<
+        &mut Text,
+        (With<OutlinerSummaryText>, Without<OutlinerRowText>),
+    >,
+    atoms: Query<(Entity, &Atom, &GlobalTransform, Option<&FormedAt>)>,
+    view: Res<OutlinerView>,
+    step: Res<SimStep>,
+    fixed_time: Res<Time<Fixed>>,
+    time: Res<Time<Real>>,
+    asset_server: Res<AssetServer>,
+    mut last_refresh: Local<f32>,
+) {
+    let hidden = panel
+        .get_single()
+        .is_ok_and(|visibility| *visibility == Visibility::Hidden);
+    let due = time.elapsed_secs() - *last_refresh >= REFRESH_INTERVAL;
+    if hidden || !(due || view.is_changed()) {
+        return;
+    }
+    *last_refresh = time.elapsed_secs();
+    let Ok((list, children)) = list.get_single() else {
+        return;
+    };
+
+    let timestep = fixed_time.timestep().as_secs_f32();
+    let mut entries: Vec<OutlinerEntry> = atoms
+        .iter()
+        .filter(|(_, atom, ..)| {
+            view.filter.is_none_or(|element| atom.element == element)
+        })
+        .map(|(entity, atom, transform, formed)| {
+            let age_steps =
+                formed.map_or(0, |formed| step.0.saturating_sub(formed.step));
+            let key = match view.sort {
+                OutlinerSort::Element => {
+                    i64::from(atom.proton_count) * 1000
+                        + i64::from(atom.count())
+                }
+                OutlinerSort::MassNumber => i64::from(atom.count()),
+                OutlinerSort::Charge => {
+                    i64::from(atom.proton_count)
+                        - i64::from(atom.electron_count)
+                }
+                OutlinerSort::Age => {
+                    i64::try_from(age_steps).unwrap_or(i64::MAX)
+                }
+            };
+            OutlinerEntry {
+                atom: entity,
+                key,
+                line: atom_line(
+                    atom,
+                    age_steps as f32 * timestep,
+                    transform.translation(),
+                ),
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| (entry.key, entry.atom));
+    if view.descending {
+        entries.reverse();
+    }
+
+    if let Ok(mut text) = summary.get_single_mut() {
+        text.0 = format!("{} of {} atoms", entries.len(), atoms.iter().len());
+    }
+
+    let existing: Vec<Entity> = children
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(row_entity) = existing.get(index) else {
+            let font = TextFont {
+                font: asset_server
+                    .load("fonts/JetBrainsMonoNerdFont-Regular.ttf"),
+                font_size: 12.0,
+                ..default()
+            };
+            commands
+                .spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(6.0), Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(4.0)),
+                    BackgroundColor(Color::NONE),
+                    OutlinerRow { atom: entry.atom },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(entry.line.clone()),
+                        font,
+                        TextColor(Color::srgb_u8(205, 214, 244)),
+                        OutlinerRowText,
+                    ));
+                })
+                .set_parent(list);
+            continue;
+        };
+        let Ok((mut row, row_children)) = rows.get_mut(*row_entity) else {
+            continue;
+        };
+        if row.atom != entry.atom {
+            row.atom = entry.atom;
+        }
+        for child in row_children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                if text.0 != entry.line {
+                    text.0.clone_from(&entry.line);
+                }
+            }
+        }
+    }
+    for row_entity in existing.iter().skip(entries.len()) {
+        commands.entity(*row_entity).despawn_recursive();
+    }
+}
+
+/// Selects the atom of a clicked row like clicking its hitbox, adding it to
+/// the selection with Shift, and highlights the hitbox of the hovered row
+pub fn interact_outliner_rows(
+    mut commands: Commands,
+    mut rows: Query<(&Interaction, &OutlinerRow, &mut BackgroundColor)>,
+    clicked: Query<(&Interaction, &OutlinerRow), Changed<Interaction>>,
+    atoms: Query<Has<Selected>, With<Atom>>,
+    selected: Query<Entity, With<Selected>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut highlighted: Local<Option<Entity>>,
+) {
+    let additive = keyboard_input.pressed(KeyCode::ShiftLeft)
+        || keyboard_input.pressed(KeyCode::ShiftRight);
+    for (interaction, row) in clicked.iter() {
+        if *interaction == Interaction::Pressed && atoms.contains(row.atom) {
+            toggle_selection(&mut commands, &selected, row.atom, additive);
+        }
+    }
+
+    let mut hovered = None;
+    for (interaction, row, mut background) in rows.iter_mut() {
+        let is_selected = atoms.get(row.atom).unwrap_or(false);
+        let color = match interaction {
+            Interaction::Hovered | Interaction::Pressed => {
+                hovered = Some(row.atom);
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::None if is_selected => SELECTED_ROW_COLOR,
+            Interaction::None => Color::NONE,
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+
+    if *highlighted == hovered {
+        return;
+    }
+    if let Some(mut entity) = highlighted.and_then(|e| commands.get_entity(e)) {
+        entity.remove::<Highlighted>();
+    }
+    if let Some(atom) = hovered.filter(|atom| atoms.contains(*atom)) {
+        commands.entity(atom).try_insert(Highlighted);
+    }
+    *highlighted = hovered;
+}
+
+/// Scrolls the list with the mouse wheel while the cursor is over it
+pub fn scroll_atom_outliner(
+    mut wheel: EventReader<MouseWheel>,
+    mut list: Query<
+        (
+            &RelativeCursorPosition,
+            &mut ScrollPosition,
+            &InheritedVisibility,
+        ),
+        With<OutlinerList>,
+    >,
+) {
+    let Ok((cursor, mut scroll, visibility)) = list.get_single_mut() else {
+        wheel.clear();
+        return;
+    };
+    if !visibility.get() || !cursor.mouse_over() {
+        wheel.clear();
+        return;
+    }
+
+    for event in wheel.read() {
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        scroll.offset_y = (scroll.offset_y - lines).max(0.0);
+    }
+}
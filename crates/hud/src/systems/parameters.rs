@@ -0,0 +1,413 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+    ui::RelativeCursorPosition,
+};
+use mechanics::{PhysicsConstants, PhysicsParameter};
+use strum::IntoEnumIterator;
+use view::TextInputFocus;
+
+use super::controls::{BUTTON_COLOR, BUTTON_HOVER_COLOR};
+use crate::{
+    HudCamera, ParameterAction, ParameterActionButton, ParameterField,
+    ParameterFieldText, ParameterPanel, ParameterSlider, ParameterSliderFill,
+    ParameterStatusText,
+};
+
+/// Where "Save preset" writes the constants, loadable with `--physics`
+const PRESET_PATH: &str = "presets/physics.ron";
+const SLIDER_WIDTH: f32 = 160.0;
+/// Force constants span orders of magnitude, so their sliders are
+/// logarithmic over this factor either side of the default
+const LOG_RANGE: f32 = 100.0;
+/// Linear sliders run from zero to this multiple of the default
+const LINEAR_RANGE: f32 = 4.0;
+// Lavender
+const FILL_COLOR: Color = Color::srgb(0.706, 0.745, 0.996);
+
+/// Slider bounds of `parameter` and whether it is logarithmic
+fn slider_range(parameter: PhysicsParameter) -> (f32, f32, bool) {
+    let default = parameter.get(&PhysicsConstants::default());
+    match parameter {
+        PhysicsParameter::GravitationalConstant
+        | PhysicsParameter::CoulombConstant
+        | PhysicsParameter::StrongForceConstant => {
+            (default / LOG_RANGE, default * LOG_RANGE, true)
+        }
+        _ => (0.0, default * LINEAR_RANGE, false),
+    }
+}
+
+fn slider_value(parameter: PhysicsParameter, fraction: f32) -> f32 {
+    let (min, max, logarithmic) = slider_range(parameter);
+    let fraction = fraction.clamp(0.0, 1.0);
+    if logarithmic {
+        min * (max / min).powf(fraction)
+    } else {
+        min + (max - min) * fraction
+    }
+}
+
+fn slider_fraction(parameter: PhysicsParameter, value: f32) -> f32 {
+    let (min, max, logarithmic) = slider_range(parameter);
+    let fraction = if logarithmic {
+        (value.max(min) / min).ln() / (max / min).ln()
+    } else {
+        (value - min) / (max - min)
+    };
+    fraction.clamp(0.0, 1.0)
+}
+
+fn format_value(value: f32) -> String {
+    if value.abs() >= 1000.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.3}")
+    }
+}
+
+pub fn setup_parameter_panel(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 13.0,
+        ..default()
+    };
+    let text_color = TextColor(Color::srgb_u8(205, 214, 244));
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(300.0),
+                left: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(6.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+            Visibility::Hidden,
+            ParameterPanel,
+        ))
+        .with_children(|parent| {
+            for parameter in PhysicsParameter::iter() {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(8.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(parameter.name()),
+                            font.clone(),
+                            text_color,
+                            Node {
+                                width: Val::Px(210.0),
+                                ..default()
+                            },
+                        ));
+
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    width: Val::Px(SLIDER_WIDTH),
+                                    height: Val::Px(10.0),
+                                    ..default()
+                                },
+                                BorderRadius::all(Val::Px(5.0)),
+                                BackgroundColor(BUTTON_COLOR),
+                                RelativeCursorPosition::default(),
+                                ParameterSlider { parameter },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Node {
+                                        height: Val::Percent(100.0),
+                                        ..default()
+                                    },
+                                    BorderRadius::all(Val::Px(5.0)),
+                                    BackgroundColor(FILL_COLOR),
+                                    PickingBehavior::IGNORE,
+                                    ParameterSliderFill { parameter },
+                                ));
+                            });
+
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    width: Val::Px(100.0),
+                                    padding: UiRect::axes(
+                                        Val::Px(6.0),
+                                        Val::Px(2.0),
+                                    ),
+                                    ..default()
+                                },
+                                BorderRadius::all(Val::Px(4.0)),
+                                BackgroundColor(BUTTON_COLOR),
+                                ParameterField {
+                                    parameter,
+                                    editing: None,
+                                },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::default(),
+                                    font.clone(),
+                                    text_color,
+                                    ParameterFieldText { parameter },
+                                ));
+                            });
+                    });
+            }
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    align_items: AlignItems::Center,
+                    margin: UiRect::top(Val::Px(4.0)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, action) in [
+                        ("Reset to defaults", ParameterAction::Reset),
+                        ("Save preset", ParameterAction::SavePreset),
+                    ] {
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(
+                                        Val::Px(8.0),
+                                        Val::Px(4.0),
+                                    ),
+                                    ..default()
+                                },
+                                BorderRadius::all(Val::Px(6.0)),
+                                BackgroundColor(BUTTON_COLOR),
+                                ParameterActionButton { action },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(label),
+                                    font.clone(),
+                                    text_color,
+                                ));
+                            });
+                    }
+
+                    parent.spawn((
+                        Text::default(),
+                        font.clone(),
+                        TextColor(Color::srgb_u8(180, 190, 254)),
+                        ParameterStatusText,
+                    ));
+                });
+        });
+}
+
+// system that toggles the parameter panel on pressing K
+pub fn toggle_parameter_panel(
+    mut panel: Query<&mut Visibility, With<ParameterPanel>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyK) {
+        for mut visibility in panel.iter_mut() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+/// Sets a parameter from where its slider is pressed, following the cursor
+/// while the button is held
+pub fn drag_parameter_sliders(
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &ParameterSlider)>,
+    mut physics: ResMut<PhysicsConstants>,
+) {
+    for (interaction, cursor, slider) in sliders.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let mut updated = *physics;
+        slider
+            .parameter
+            .set(&mut updated, slider_value(slider.parameter, position.x));
+        // Holding still must not mark the constants changed every frame
+        physics.set_if_neq(updated);
+    }
+}
+
+/// Starts editing the clicked field, giving it keyboard focus
+pub fn click_parameter_fields(
+    mut fields: Query<(Entity, Ref<Interaction>, &mut ParameterField)>,
+    mut focus: ResMut<TextInputFocus>,
+    physics: Res<PhysicsConstants>,
+) {
+    let Some(clicked) = fields
+        .iter()
+        .find(|(_, interaction, _)| {
+            interaction.is_changed() && **interaction == Interaction::Pressed
+        })
+        .map(|(entity, ..)| entity)
+    else {
+        return;
+    };
+
+    for (entity, _, mut field) in fields.iter_mut() {
+        if entity == clicked {
+            field.editing = Some(format_value(field.parameter.get(&physics)));
+        } else if field.editing.is_some() {
+            field.editing = None;
+        }
+    }
+    focus.0 = Some(clicked);
+}
+
+/// Types into the focused field, committing the value on Enter and
+/// discarding it on Escape
+pub fn edit_parameter_fields(
+    mut events: EventReader<KeyboardInput>,
+    mut fields: Query<&mut ParameterField>,
+    mut focus: ResMut<TextInputFocus>,
+    mut physics: ResMut<PhysicsConstants>,
+) {
+    let Some(mut field) =
+        focus.0.and_then(|entity| fields.get_mut(entity).ok())
+    else {
+        events.clear();
+        return;
+    };
+
+    let parameter = field.parameter;
+    for event in events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        let Some(buffer) = field.editing.as_mut() else {
+            break;
+        };
+        match &event.logical_key {
+            Key::Character(text) => buffer.extend(
+                text.chars()
+                    .filter(|c| c.is_ascii_digit() || "+-.eE".contains(*c)),
+            ),
+            Key::Backspace => {
+                buffer.pop();
+            }
+            Key::Enter => {
+                match buffer.parse::<f32>() {
+                    Ok(value) if PhysicsParameter::accepts(value) => {
+                        parameter.set(&mut physics, value);
+                    }
+                    _ => warn!(
+                        "Invalid value for {}: {buffer}",
+                        parameter.name()
+                    ),
+                }
+                field.editing = None;
+            }
+            Key::Escape => field.editing = None,
+            _ => {}
+        }
+    }
+
+    if field.editing.is_none() {
+        focus.0 = None;
+    }
+}
+
+pub fn click_parameter_actions(
+    mut buttons: Query<
+        (&Interaction, &ParameterActionButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut status: Query<&mut Text, With<ParameterStatusText>>,
+    mut physics: ResMut<PhysicsConstants>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                let message = match button.action {
+                    ParameterAction::Reset => {
+                        *physics = PhysicsConstants::default();
+                        "Reset".to_string()
+                    }
+                    ParameterAction::SavePreset => {
+                        match physics.save(PRESET_PATH) {
+                            Ok(()) => {
+                                info!("Saved physics preset to {PRESET_PATH}");
+                                format!("Saved {PRESET_PATH}")
+                            }
+                            Err(err) => {
+                                error!("{err}");
+                                "Save failed".to_string()
+                            }
+                        }
+                    }
+                };
+                if let Ok(mut text) = status.get_single_mut() {
+                    text.0 = message;
+                }
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+/// Moves the sliders and fields to the current constants, wherever they
+/// were changed from
+pub fn update_parameter_panel(
+    physics: Res<PhysicsConstants>,
+    fields: Query<&ParameterField>,
+    changed_fields: Query<(), Changed<ParameterField>>,
+    mut fills: Query<(&ParameterSliderFill, &mut Node)>,
+    mut texts: Query<(&ParameterFieldText, &mut Text)>,
+) {
+    if !physics.is_changed() && changed_fields.is_empty() {
+        return;
+    }
+
+    for (fill, mut node) in fills.iter_mut() {
+        let fraction =
+            slider_fraction(fill.parameter, fill.parameter.get(&physics));
+        node.width = Val::Percent(fraction * 100.0);
+    }
+
+    for (field_text, mut text) in texts.iter_mut() {
+        let editing = fields
+            .iter()
+            .find(|field| field.parameter == field_text.parameter)
+            .and_then(|field| field.editing.as_ref());
+        let label = match editing {
+            Some(buffer) => format!("{buffer}_"),
+            None => format_value(field_text.parameter.get(&physics)),
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
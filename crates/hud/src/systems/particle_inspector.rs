@@ -0,0 +1,264 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_dynamics::Velocity;
+use mechanics::{particle::Particle, Atom, Forces, Selected};
+use view::{select_only, ParticleInspector};
+
+use super::controls::{BUTTON_COLOR, BUTTON_HOVER_COLOR};
+use crate::{
+    HudCamera, ParticleTooltip, ParticleTooltipText, PinnedParticleAction,
+    PinnedParticleButton, PinnedParticlePanel, PinnedParticleText,
+};
+
+/// Offset of the tooltip from the cursor, so it doesn't cover the particle
+const TOOLTIP_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+fn particle_details(
+    particle: &Particle,
+    velocity: &Velocity,
+    forces: &Forces,
+) -> String {
+    format!(
+        "{:?}\n\
+         mass       {:.3}\n\
+         charge     {:+.2}\n\
+         speed      {:.2}\n\
+         force      {:.3e}\n\
+         \u{20} gravity  {:.3e}\n\
+         \u{20} EM       {:.3e}\n\
+         \u{20} strong   {:.3e}\n\
+         \u{20} external {:.3e}",
+        particle.kind,
+        particle.mass,
+        particle.charge.charge(),
+        velocity.value.length(),
+        forces.total().length(),
+        forces.gravity.length(),
+        forces.electromagnetic.length(),
+        forces.strong.length(),
+        forces.external.length(),
+    )
+}
+
+pub fn setup_particle_inspector(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 12.0,
+        ..default()
+    };
+    let text_color = TextColor(Color::srgb_u8(205, 214, 244));
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(Color::srgba_u8(30, 30, 46, 230)),
+            Visibility::Hidden,
+            // Under the cursor it would steal the hover from the particle
+            PickingBehavior::IGNORE,
+            ParticleTooltip,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                font.clone(),
+                text_color,
+                PickingBehavior::IGNORE,
+                ParticleTooltipText,
+            ));
+        });
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(40.0),
+                right: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+            Visibility::Hidden,
+            PinnedParticlePanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                font.clone(),
+                text_color,
+                PinnedParticleText,
+            ));
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, action) in [
+                        ("Select atom", PinnedParticleAction::SelectAtom),
+                        ("Unpin", PinnedParticleAction::Unpin),
+                    ] {
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(
+                                        Val::Px(8.0),
+                                        Val::Px(4.0),
+                                    ),
+                                    ..default()
+                                },
+                                BorderRadius::all(Val::Px(6.0)),
+                                BackgroundColor(BUTTON_COLOR),
+                                PinnedParticleButton { action },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(label),
+                                    font.clone(),
+                                    text_color,
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+/// Shows the hovered particle's details next to the cursor
+pub fn update_particle_tooltip(
+    inspector: Res<ParticleInspector>,
+    particles: Query<(&Particle, &Velocity, &Forces)>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut tooltip: Query<(&mut Node, &mut Visibility), With<ParticleTooltip>>,
+    mut text: Query<&mut Text, With<ParticleTooltipText>>,
+) {
+    let Ok((mut node, mut visibility)) = tooltip.get_single_mut() else {
+        return;
+    };
+    let hovered = inspector
+        .hovered
+        .and_then(|entity| particles.get(entity).ok());
+    let cursor = window.get_single().ok().and_then(Window::cursor_position);
+
+    let (Some((particle, velocity, forces)), Some(cursor)) = (hovered, cursor)
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    node.left = Val::Px(cursor.x + TOOLTIP_OFFSET.x);
+    node.top = Val::Px(cursor.y + TOOLTIP_OFFSET.y);
+    if let Ok(mut text) = text.get_single_mut() {
+        text.0 = particle_details(particle, velocity, forces);
+    }
+}
+
+/// Shows the pinned particle in the inspector panel, offering to select
+/// its atom while it is bound in one
+pub fn update_pinned_particle(
+    mut inspector: ResMut<ParticleInspector>,
+    particles: Query<(&Particle, &Velocity, &Forces, &Transform)>,
+    atoms: Query<&Atom>,
+    mut panel: Query<&mut Visibility, With<PinnedParticlePanel>>,
+    mut text: Query<&mut Text, With<PinnedParticleText>>,
+    mut buttons: Query<(&PinnedParticleButton, &mut Node)>,
+) {
+    let Ok(mut visibility) = panel.get_single_mut() else {
+        return;
+    };
+    let Some(pinned) = inspector.pinned else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let Ok((particle, velocity, forces, transform)) = particles.get(pinned)
+    else {
+        // The particle is gone, e.g. a photon that was absorbed
+        inspector.pinned = None;
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    let atom = atoms
+        .iter()
+        .find(|atom| atom.constituent_particles.contains(&pinned));
+    if let Ok(mut text) = text.get_single_mut() {
+        let position = transform.translation;
+        text.0 = format!(
+            "{}\n\
+             position   ({:.0}, {:.0}, {:.0})\n\
+             bound in   {}",
+            particle_details(particle, velocity, forces),
+            position.x,
+            position.y,
+            position.z,
+            atom.map_or_else(
+                || "no atom".to_string(),
+                |atom| atom.element.to_string()
+            ),
+        );
+    }
+
+    for (button, mut node) in buttons.iter_mut() {
+        let display = match button.action {
+            PinnedParticleAction::SelectAtom if atom.is_none() => Display::None,
+            _ => Display::Flex,
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+pub fn click_pinned_particle_buttons(
+    mut commands: Commands,
+    mut buttons: Query<
+        (&Interaction, &PinnedParticleButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut inspector: ResMut<ParticleInspector>,
+    atoms: Query<(Entity, &Atom)>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                match button.action {
+                    PinnedParticleAction::SelectAtom => {
+                        let parent = inspector.pinned.and_then(|pinned| {
+                            atoms.iter().find(|(_, atom)| {
+                                atom.constituent_particles.contains(&pinned)
+                            })
+                        });
+                        if let Some((entity, _)) = parent {
+                            select_only(&mut commands, &selected, entity);
+                        }
+                    }
+                    PinnedParticleAction::Unpin => inspector.pinned = None,
+                }
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
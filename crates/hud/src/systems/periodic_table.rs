@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use mechanics::{element::Element, Atom, Selected, SimStats};
+use strum::IntoEnumIterator;
+use view::{select_only, CameraCommand, MainCamera};
+
+use crate::{HudCamera, PeriodicCell, PeriodicCellCounts, PeriodicTablePanel};
+
+const CELL_WIDTH: f32 = 34.0;
+const CELL_HEIGHT: f32 = 38.0;
+const COLUMNS: u16 = 18;
+/// Seven periods, the hypothetical eighth, and the lanthanide and actinide
+/// rows
+const ROWS: u16 = 10;
+const CELL_GAP: f32 = 2.0;
+const PANEL_PADDING: f32 = 10.0;
+/// Outer width of the panel, for placing others beside it
+pub(super) const PANEL_WIDTH: f32 = COLUMNS as f32 * CELL_WIDTH
+    + (COLUMNS - 1) as f32 * CELL_GAP
+    + 2.0 * PANEL_PADDING;
+// Surface0
+const UNDISCOVERED_COLOR: Color = Color::srgba(0.192, 0.196, 0.267, 0.8);
+
+pub fn setup_periodic_table(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let jbm_bold: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Bold.ttf");
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(PANEL_PADDING)),
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(
+                    COLUMNS, CELL_WIDTH,
+                ),
+                grid_template_rows: RepeatedGridTrack::px(ROWS, CELL_HEIGHT),
+                row_gap: Val::Px(CELL_GAP),
+                column_gap: Val::Px(CELL_GAP),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+            Visibility::Hidden,
+            PeriodicTablePanel,
+        ))
+        .with_children(|parent| {
+            for element in Element::iter() {
+                let (row, column) = element.table_position();
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            grid_row: GridPlacement::start(row as i16 + 1),
+                            grid_column: GridPlacement::start(
+                                column as i16 + 1,
+                            ),
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        BorderRadius::all(Val::Px(4.0)),
+                        BackgroundColor(UNDISCOVERED_COLOR),
+                        PeriodicCell { element },
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(element.symbol().to_string()),
+                            TextFont {
+                                font: jbm_bold.clone(),
+                                font_size: 14.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb_u8(30, 30, 46)),
+                        ));
+                        parent.spawn((
+                            Text::default(),
+                            TextFont {
+                                font: jbm_regular.clone(),
+                                font_size: 9.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb_u8(30, 30, 46)),
+                            PeriodicCellCounts { element },
+                        ));
+                    });
+            }
+        });
+}
+
+// system that toggles the periodic table on pressing P
+pub fn toggle_periodic_table(
+    mut panel: Query<&mut Visibility, With<PeriodicTablePanel>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        for mut visibility in panel.iter_mut() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+/// Lights up every element that has formed at least once, and shows its
+/// current and peak counts
+pub fn update_periodic_table(
+    stats: Res<SimStats>,
+    mut cells: Query<(&PeriodicCell, &mut BackgroundColor)>,
+    mut counts: Query<(&PeriodicCellCounts, &mut Text)>,
+) {
+    if !stats.is_changed() {
+        return;
+    }
+
+    let count = |element: &Element| {
+        (
+            stats
+                .element_counts
+                .get(element)
+                .copied()
+                .unwrap_or_default(),
+            stats
+                .peak_element_counts
+                .get(element)
+                .copied()
+                .unwrap_or_default(),
+        )
+    };
+
+    for (cell, mut background) in cells.iter_mut() {
+        let (_, peak) = count(&cell.element);
+        background.0 = if peak > 0 {
+            cell.element.color()
+        } else {
+            UNDISCOVERED_COLOR
+        };
+    }
+
+    for (cell, mut text) in counts.iter_mut() {
+        let (current, peak) = count(&cell.element);
+        let label = if peak > 0 {
+            format!("{current}/{peak}")
+        } else {
+            String::new()
+        };
+        // Avoid marking unchanged text for relayout every step
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+/// Selects the atom of the clicked element that is nearest to the camera,
+/// and focuses the camera on it
+pub fn click_periodic_cell(
+    mut commands: Commands,
+    cells: Query<(&Interaction, &PeriodicCell), Changed<Interaction>>,
+    atoms: Query<(Entity, &Atom, &GlobalTransform)>,
+    selected: Query<Entity, With<Selected>>,
+    camera: Query<&GlobalTransform, With<MainCamera>>,
+    mut camera_commands: EventWriter<CameraCommand>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+
+    for (interaction, cell) in cells.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let nearest = atoms
+            .iter()
+            .filter(|(_, atom, _)| atom.element == cell.element)
+            .min_by(|(_, _, a), (_, _, b)| {
+                let a = a.translation().distance_squared(camera.translation());
+                let b = b.translation().distance_squared(camera.translation());
+                a.total_cmp(&b)
+            });
+        let Some((entity, ..)) = nearest else {
+            continue;
+        };
+
+        select_only(&mut commands, &selected, entity);
+        camera_commands.send(CameraCommand::FocusAtom(entity));
+    }
+}
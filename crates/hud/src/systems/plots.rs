@@ -0,0 +1,353 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use mechanics::{element::Element, StatsHistory, StatsSample};
+
+use super::controls::{BUTTON_COLOR, BUTTON_HOVER_COLOR};
+use crate::{
+    HudCamera, PlotPanel, PlotSeries, PlotTimeText, PlotToggle, PlotToggleText,
+    PlotView,
+};
+
+/// Pixels kept clear above and below the lines
+const PLOT_MARGIN: f32 = 4.0;
+const GRID_LINES: u32 = 4;
+// Surface2
+const GRID_COLOR: [u8; 4] = [88, 91, 112, 120];
+// Overlay0
+const DISABLED_TEXT_COLOR: Color = Color::srgb(0.424, 0.439, 0.525);
+
+/// Values of one line, oldest first
+struct Line {
+    color: Color,
+    values: Vec<f32>,
+}
+
+/// Lines drawn for `series` and the range they are scaled to
+fn series_lines(
+    series: PlotSeries,
+    samples: &[&StatsSample],
+) -> (Vec<Line>, (f32, f32)) {
+    let count = |value: usize| value as f32;
+    let lines = match series {
+        PlotSeries::TotalEnergy => vec![Line {
+            color: series.color(),
+            values: samples.iter().map(|s| s.total_energy).collect(),
+        }],
+        PlotSeries::Temperature => vec![Line {
+            color: series.color(),
+            values: samples.iter().map(|s| s.temperature).collect(),
+        }],
+        PlotSeries::FreeNucleons => vec![Line {
+            color: series.color(),
+            values: samples.iter().map(|s| count(s.free_nucleons)).collect(),
+        }],
+        PlotSeries::Atoms => vec![Line {
+            color: series.color(),
+            values: samples.iter().map(|s| count(s.atom_count)).collect(),
+        }],
+        PlotSeries::Elements => {
+            let elements: BTreeSet<Element> = samples
+                .iter()
+                .flat_map(|s| s.element_counts.keys().copied())
+                .collect();
+            elements
+                .into_iter()
+                .map(|element| Line {
+                    color: element.color(),
+                    values: samples
+                        .iter()
+                        .map(|s| {
+                            count(
+                                s.element_counts
+                                    .get(&element)
+                                    .copied()
+                                    .unwrap_or_default(),
+                            )
+                        })
+                        .collect(),
+                })
+                .collect()
+        }
+    };
+
+    let values = lines.iter().flat_map(|line| line.values.iter().copied());
+    let (mut min, mut max) = values
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    // Counts read best from zero, energy and temperature from their range
+    if !matches!(series, PlotSeries::TotalEnergy | PlotSeries::Temperature) {
+        min = min.min(0.0);
+    }
+    if !min.is_finite() || !max.is_finite() {
+        (min, max) = (0.0, 1.0);
+    } else if max - min < f32::EPSILON {
+        let pad = (max.abs() * 0.1).max(1.0);
+        (min, max) = (min - pad, max + pad);
+    }
+    (lines, (min, max))
+}
+
+fn format_value(value: f32) -> String {
+    if value.abs() >= 1e4 {
+        format!("{value:.2e}")
+    } else if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+fn set_pixel(image: &mut Image, x: u32, y: u32, color: [u8; 4]) {
+    if x >= PlotView::WIDTH || y >= PlotView::HEIGHT {
+        return;
+    }
+    let index = ((y * PlotView::WIDTH + x) * 4) as usize;
+    image.data[index..index + 4].copy_from_slice(&color);
+}
+
+/// Two pixels thick, stepping once per pixel along the longer axis
+fn draw_line(image: &mut Image, from: Vec2, to: Vec2, color: [u8; 4]) {
+    let steps = (to - from).abs().max_element().ceil().max(1.0);
+    for i in 0..=steps as u32 {
+        let point = from.lerp(to, i as f32 / steps).round();
+        set_pixel(image, point.x as u32, point.y as u32, color);
+        set_pixel(image, point.x as u32, point.y as u32 + 1, color);
+    }
+}
+
+pub fn setup_plot_panel(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+    plot: Res<PlotView>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 12.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            // Full width only to centre the panel above the control strip
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(60.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+            Visibility::Hidden,
+            PlotPanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(8.0)),
+                        row_gap: Val::Px(6.0),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+                ))
+                .with_children(|parent| {
+                    parent
+                        .spawn(Node {
+                            max_width: Val::Px(PlotView::WIDTH as f32),
+                            flex_wrap: FlexWrap::Wrap,
+                            column_gap: Val::Px(4.0),
+                            row_gap: Val::Px(4.0),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for series in PlotSeries::ALL {
+                                parent
+                                    .spawn((
+                                        Button,
+                                        Node {
+                                            padding: UiRect::axes(
+                                                Val::Px(6.0),
+                                                Val::Px(2.0),
+                                            ),
+                                            ..default()
+                                        },
+                                        BorderRadius::all(Val::Px(4.0)),
+                                        BackgroundColor(BUTTON_COLOR),
+                                        PlotToggle { series },
+                                    ))
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            Text::new(series.label()),
+                                            font.clone(),
+                                            TextColor(DISABLED_TEXT_COLOR),
+                                            PlotToggleText { series },
+                                        ));
+                                    });
+                            }
+                        });
+
+                    parent.spawn((
+                        Node {
+                            width: Val::Px(PlotView::WIDTH as f32),
+                            height: Val::Px(PlotView::HEIGHT as f32),
+                            ..default()
+                        },
+                        ImageNode::new(plot.image.clone()),
+                    ));
+
+                    parent
+                        .spawn(Node {
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for latest in [false, true] {
+                                parent.spawn((
+                                    Text::default(),
+                                    font.clone(),
+                                    TextColor(Color::srgb_u8(166, 173, 200)),
+                                    PlotTimeText { latest },
+                                ));
+                            }
+                        });
+                });
+        });
+}
+
+// system that toggles the plot panel on pressing G
+pub fn toggle_plot_panel(
+    mut panel: Query<&mut Visibility, With<PlotPanel>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        for mut visibility in panel.iter_mut() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+pub fn click_plot_toggles(
+    mut buttons: Query<
+        (&Interaction, &PlotToggle, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut plot: ResMut<PlotView>,
+) {
+    for (interaction, toggle, mut background) in buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                if !plot.series.remove(&toggle.series) {
+                    plot.series.insert(toggle.series);
+                }
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+/// Redraws the chart whenever a sample is added, a series toggled or the
+/// panel opened, each series auto-scaled to its own range over the samples
+/// shown
+pub fn update_plot(
+    history: Res<StatsHistory>,
+    plot: Res<PlotView>,
+    panel: Query<Ref<Visibility>, With<PlotPanel>>,
+    mut images: ResMut<Assets<Image>>,
+    mut toggles: Query<(&PlotToggleText, &mut Text, &mut TextColor)>,
+    mut times: Query<(&PlotTimeText, &mut Text), Without<PlotToggleText>>,
+) {
+    let panel = panel.get_single().ok();
+    // Samples may have come in while it was hidden, or paused without any
+    let toggled = panel.as_ref().is_some_and(DetectChanges::is_changed);
+    if !history.is_changed() && !plot.is_changed() && !toggled {
+        return;
+    }
+    // Nothing to see, so skip rasterising every sample
+    if panel.is_some_and(|visibility| *visibility == Visibility::Hidden)
+        && !plot.is_changed()
+    {
+        return;
+    }
+    let Some(image) = images.get_mut(&plot.image) else {
+        return;
+    };
+
+    image.data.fill(0);
+    for i in 1..GRID_LINES {
+        let y = PlotView::HEIGHT * i / GRID_LINES;
+        for x in 0..PlotView::WIDTH {
+            set_pixel(image, x, y, GRID_COLOR);
+        }
+    }
+
+    let samples: Vec<&StatsSample> = history.samples.iter().collect();
+    let (start, end) = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => (first.time, last.time),
+        _ => (0.0, 0.0),
+    };
+    let width = (PlotView::WIDTH - 1) as f32;
+    let height = PlotView::HEIGHT as f32 - 2.0 * PLOT_MARGIN;
+
+    for (toggle, mut text, mut color) in toggles.iter_mut() {
+        let series = toggle.series;
+        if !plot.series.contains(&series) {
+            text.0 = series.label().to_string();
+            color.0 = DISABLED_TEXT_COLOR;
+            continue;
+        }
+
+        let (lines, (min, max)) = series_lines(series, &samples);
+        text.0 = format!(
+            "{} {}..{}",
+            series.label(),
+            format_value(min),
+            format_value(max)
+        );
+        color.0 = series.color();
+
+        if end <= start {
+            continue;
+        }
+        for line in lines {
+            let rgba = line.color.to_srgba().to_u8_array();
+            let points: Vec<Vec2> = samples
+                .iter()
+                .zip(&line.values)
+                .map(|(sample, value)| {
+                    Vec2::new(
+                        (sample.time - start) / (end - start) * width,
+                        PLOT_MARGIN
+                            + (1.0 - (value - min) / (max - min)) * height,
+                    )
+                })
+                .collect();
+            for pair in points.windows(2) {
+                draw_line(image, pair[0], pair[1], rgba);
+            }
+        }
+    }
+
+    for (time, mut text) in times.iter_mut() {
+        text.0 = if time.latest {
+            format!("{end:.1}s")
+        } else {
+            format!("{start:.1}s")
+        };
+    }
+}
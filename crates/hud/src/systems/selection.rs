@@ -0,0 +1,261 @@
+use bevy::prelude::*;
+use bevy_dynamics::Velocity;
+use mechanics::particle::{Kind, Particle};
+use mechanics::{Frozen, SimStep};
+use view::{Selection, SelectionCommand, ToolCommand, PALETTE};
+
+use super::controls::{BUTTON_COLOR, BUTTON_HOVER_COLOR};
+use crate::{
+    HudCamera, SelectionAction, SelectionButton, SelectionPanel,
+    SelectionStatsText,
+};
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    font: &TextFont,
+    label: &str,
+    action: SelectionAction,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(BUTTON_COLOR),
+            SelectionButton { action },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                font.clone(),
+                TextColor(Color::srgb_u8(205, 214, 244)),
+            ));
+        });
+}
+
+pub fn setup_selection_panel(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 12.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            Node {
+                position_type: PositionType::Absolute,
+                // Above the nucleus inspector
+                bottom: Val::Px(320.0),
+                left: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+            Visibility::Hidden,
+            SelectionPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                font.clone(),
+                TextColor(Color::srgb_u8(205, 214, 244)),
+                SelectionStatsText,
+            ));
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, action) in [
+                        ("Delete", SelectionAction::Delete),
+                        ("Freeze/Thaw", SelectionAction::ToggleFrozen),
+                        ("Save prefab", SelectionAction::SavePrefab),
+                        ("Deselect", SelectionAction::Clear),
+                    ] {
+                        spawn_button(parent, &font, label, action);
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(4.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for color in PALETTE {
+                        parent
+                            .spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::all(Val::Px(3.0)),
+                                    ..default()
+                                },
+                                BorderRadius::all(Val::Px(6.0)),
+                                BackgroundColor(BUTTON_COLOR),
+                                SelectionButton {
+                                    action: SelectionAction::Recolour(Some(
+                                        color,
+                                    )),
+                                },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Node {
+                                        width: Val::Px(14.0),
+                                        height: Val::Px(14.0),
+                                        ..default()
+                                    },
+                                    BorderRadius::all(Val::Px(4.0)),
+                                    BackgroundColor(color),
+                                    PickingBehavior::IGNORE,
+                                ));
+                            });
+                    }
+                    spawn_button(
+                        parent,
+                        &font,
+                        "Reset colour",
+                        SelectionAction::Recolour(None),
+                    );
+                });
+        });
+}
+
+pub fn click_selection_buttons(
+    mut buttons: Query<
+        (&Interaction, &SelectionButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut tool_commands: EventWriter<ToolCommand>,
+    mut selection_commands: EventWriter<SelectionCommand>,
+    step: Res<SimStep>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                match button.action {
+                    SelectionAction::Delete => {
+                        tool_commands.send(ToolCommand::DeleteSelection);
+                    }
+                    SelectionAction::ToggleFrozen => {
+                        selection_commands.send(SelectionCommand::ToggleFrozen);
+                    }
+                    SelectionAction::Recolour(color) => {
+                        selection_commands
+                            .send(SelectionCommand::Recolour(color));
+                    }
+                    SelectionAction::SavePrefab => {
+                        selection_commands.send(SelectionCommand::SavePrefab(
+                            format!("selection-{}", step.0),
+                        ));
+                    }
+                    SelectionAction::Clear => {
+                        selection_commands.send(SelectionCommand::Clear);
+                    }
+                }
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+/// Shows the panel while anything is selected, with totals over every
+/// particle in the selection
+pub fn update_selection_panel(
+    selection: Res<Selection>,
+    particles: Query<(&Particle, &Transform, &Velocity, Has<Frozen>)>,
+    mut panel: Query<&mut Visibility, With<SelectionPanel>>,
+    mut text: Query<&mut Text, With<SelectionStatsText>>,
+) {
+    let Ok(mut visibility) = panel.get_single_mut() else {
+        return;
+    };
+    if selection.is_empty() {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+
+    let members: Vec<_> = selection
+        .members
+        .iter()
+        .filter_map(|entity| particles.get(*entity).ok())
+        .collect();
+    let count = members.len().max(1) as f32;
+    let mass: f32 = members.iter().map(|(particle, ..)| particle.mass).sum();
+    let charge: f32 = members
+        .iter()
+        .map(|(particle, ..)| particle.charge.charge())
+        .sum();
+    let center = members
+        .iter()
+        .map(|(particle, transform, ..)| transform.translation * particle.mass)
+        .sum::<Vec3>()
+        / mass.max(f32::EPSILON);
+    let speed = members
+        .iter()
+        .map(|(_, _, velocity, _)| velocity.value.length())
+        .sum::<f32>()
+        / count;
+    let kinetic: f32 = members
+        .iter()
+        .map(|(particle, _, velocity, _)| {
+            0.5 * particle.mass * velocity.value.length_squared()
+        })
+        .sum();
+    let frozen = members.iter().filter(|(.., frozen)| *frozen).count();
+    let kinds = [
+        (Kind::Proton, "p"),
+        (Kind::Neutron, "n"),
+        (Kind::Electron, "e"),
+        (Kind::Photon, "γ"),
+    ]
+    .map(|(kind, label)| {
+        let count = members
+            .iter()
+            .filter(|(particle, ..)| particle.kind == kind)
+            .count();
+        format!("{label} {count}")
+    })
+    .join("  ");
+
+    if let Ok(mut text) = text.get_single_mut() {
+        text.0 = format!(
+            "{} atoms, {} particles\n\
+             {kinds}\n\
+             mass      {mass:.2}\n\
+             charge    {charge:+.2}\n\
+             centre    ({:.0}, {:.0}, {:.0})\n\
+             speed     {speed:.2} mean\n\
+             kinetic   {kinetic:.3e}\n\
+             frozen    {frozen}/{}",
+            selection.atoms.len(),
+            members.len(),
+            center.x,
+            center.y,
+            center.z,
+            members.len(),
+        );
+    }
+}
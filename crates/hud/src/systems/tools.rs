@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+use view::{ToolCommand, ToolMode, Tools, UndoStack};
+
+use super::controls::{BUTTON_COLOR, BUTTON_HOVER_COLOR};
+use crate::{
+    HudCamera, ToolButton, ToolButtonText, ToolCommandButton, ToolKindButton,
+    ToolKindText, ToolStatusText,
+};
+
+// Lavender
+const ACTIVE_TEXT_COLOR: Color = Color::srgb(0.706, 0.745, 0.996);
+// Text
+const TEXT_COLOR: Color = Color::srgb(0.804, 0.839, 0.957);
+
+fn toolbar_button(
+    parent: &mut ChildBuilder,
+    font: &TextFont,
+    label: String,
+    button: impl Bundle,
+    text: impl Bundle,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(BUTTON_COLOR),
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                font.clone(),
+                TextColor(TEXT_COLOR),
+                text,
+            ));
+        });
+}
+
+pub fn setup_toolbar(
+    mut commands: Commands,
+    hud_camera: Query<Entity, With<HudCamera>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(hud_camera) = hud_camera.get_single() else {
+        return;
+    };
+    let jbm_regular: Handle<Font> =
+        asset_server.load("fonts/JetBrainsMonoNerdFont-Regular.ttf");
+    let font = TextFont {
+        font: jbm_regular,
+        font_size: 14.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            TargetCamera(hud_camera),
+            // Full width only to centre the toolbar, so it must not swallow
+            // clicks meant for the scene
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            PickingBehavior::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(6.0)),
+                        column_gap: Val::Px(6.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::srgba_u8(69, 71, 90, 200)),
+                ))
+                .with_children(|parent| {
+                    for (i, mode) in ToolMode::iter().enumerate() {
+                        toolbar_button(
+                            parent,
+                            &font,
+                            format!("{} {mode}", i + 1),
+                            ToolButton { mode },
+                            ToolButtonText { mode },
+                        );
+                    }
+                    toolbar_button(
+                        parent,
+                        &font,
+                        String::new(),
+                        ToolKindButton,
+                        ToolKindText,
+                    );
+                    for (label, command) in [
+                        ("Undo", ToolCommand::Undo),
+                        ("Delete selection", ToolCommand::DeleteSelection),
+                    ] {
+                        toolbar_button(
+                            parent,
+                            &font,
+                            label.to_string(),
+                            ToolCommandButton { command },
+                            (),
+                        );
+                    }
+
+                    parent.spawn((
+                        Text::default(),
+                        font.clone(),
+                        TextColor(Color::srgb_u8(166, 173, 200)),
+                        ToolStatusText,
+                    ));
+                });
+        });
+}
+
+pub fn click_toolbar(
+    mut buttons: Query<
+        (
+            &Interaction,
+            Option<&ToolButton>,
+            Option<&ToolCommandButton>,
+            Has<ToolKindButton>,
+            &mut BackgroundColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut tools: ResMut<Tools>,
+    mut commands: EventWriter<ToolCommand>,
+) {
+    for (interaction, tool, command, kind, mut background) in buttons.iter_mut()
+    {
+        background.0 = match interaction {
+            Interaction::Pressed => {
+                if let Some(tool) = tool {
+                    tools.mode = tool.mode;
+                }
+                if let Some(command) = command {
+                    commands.send(command.command);
+                }
+                if kind {
+                    tools.cycle_kind();
+                }
+                BUTTON_HOVER_COLOR
+            }
+            Interaction::Hovered => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+/// Marks the active tool and shows the spawned kind and undo depth
+pub fn update_toolbar(
+    tools: Res<Tools>,
+    undo: Res<UndoStack>,
+    mut tool_texts: Query<(&ToolButtonText, &mut TextColor)>,
+    mut kind_text: Query<&mut Text, With<ToolKindText>>,
+    mut status: Query<&mut Text, (With<ToolStatusText>, Without<ToolKindText>)>,
+) {
+    if tools.is_changed() {
+        for (text, mut color) in tool_texts.iter_mut() {
+            color.0 = if text.mode == tools.mode {
+                ACTIVE_TEXT_COLOR
+            } else {
+                TEXT_COLOR
+            };
+        }
+        if let Ok(mut text) = kind_text.get_single_mut() {
+            text.0 = format!("Q {:?}", tools.kind);
+        }
+    }
+
+    if undo.is_changed() {
+        if let Ok(mut text) = status.get_single_mut() {
+            text.0 = format!("{} to undo", undo.actions.len());
+        }
+    }
+}
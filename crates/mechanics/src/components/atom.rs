@@ -14,6 +14,13 @@ pub struct Atom {
     pub constituent_particles: Vec<Entity>,
 }
 
+/// Step at which an atom first formed. Atoms keep their entity while
+/// nucleons come and go, so this is when the nucleus started to exist.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FormedAt {
+    pub step: u64,
+}
+
 impl Atom {
     pub fn new(
         proton_count: u32,
@@ -63,8 +70,3 @@ impl std::fmt::Display for Atom {
         )
     }
 }
-
-#[derive(Component, Debug, Default)]
-pub struct AtomHitbox {
-    pub selected: bool,
-}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+/// The forces acting on a particle during the last fixed step, split by
+/// interaction
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Forces {
+    pub gravity: Vec3,
+    pub electromagnetic: Vec3,
+    pub strong: Vec3,
+    /// From an [`ExternalForce`], e.g. a particle dragged by hand
+    pub external: Vec3,
+}
+
+impl Forces {
+    pub fn total(&self) -> Vec3 {
+        self.gravity + self.electromagnetic + self.strong + self.external
+    }
+}
+
+/// A force from outside the simulation, added to a particle's interactions
+/// for as long as the component is present
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct ExternalForce(pub Vec3);
+
+/// Holds a particle in place. It keeps exerting forces on the others, but its
+/// own velocity and acceleration are zeroed every step.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Frozen;
@@ -1,12 +1,16 @@
 pub mod atom;
 pub mod electron;
+pub mod forces;
 pub mod neutron;
 pub mod particle;
 pub mod photon;
 pub mod proton;
+pub mod selected;
 
 pub use atom::*;
 pub use electron::*;
+pub use forces::*;
 pub use neutron::*;
 pub use particle::*;
 pub use proton::*;
+pub use selected::*;
@@ -1,14 +1,18 @@
 use bevy::prelude::*;
 use bevy_dynamics::Acceleration;
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 use super::{
     electron::{ELECTRON_CHARGE, ELECTRON_MASS},
+    forces::Forces,
     neutron::{NEUTRON_CHARGE, NEUTRON_MASS},
     proton::{PROTON_CHARGE, PROTON_MASS},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize,
+)]
 pub enum Kind {
     Proton,
     Neutron,
@@ -34,7 +38,7 @@ impl Charge {
 }
 
 #[derive(Component, Debug, Clone, Copy)]
-#[require(Acceleration)]
+#[require(Acceleration, Forces)]
 pub struct Particle {
     pub kind: Kind,
     pub mass: f32,
@@ -43,6 +47,15 @@ pub struct Particle {
 }
 
 impl Particle {
+    pub fn from_kind(kind: Kind) -> Self {
+        match kind {
+            Kind::Proton => Self::proton(),
+            Kind::Neutron => Self::neutron(),
+            Kind::Electron => Self::electron(),
+            Kind::Photon => Self::photon(),
+        }
+    }
+
     pub fn proton() -> Self {
         Self {
             kind: Kind::Proton,
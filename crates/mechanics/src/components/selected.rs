@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// Marks an atom or particle as part of the current selection.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Selected;
@@ -6,7 +6,18 @@ use strum_macros::{EnumIter, EnumString};
 
 // pub const MAX_ATOMIC_NUMBER: usize = 119;
 
-#[derive(EnumString, Debug, EnumIter)]
+#[derive(
+    EnumString,
+    Debug,
+    EnumIter,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
 pub enum Element {
     Hydrogen,      // 1
     Helium,        // 2
@@ -137,6 +148,35 @@ impl Element {
         Self::iter().nth(proton_count - 1)
     }
 
+    pub fn atomic_number(&self) -> usize {
+        *self as usize + 1
+    }
+
+    /// Row and column, both from 0, in the 18-column periodic table. The
+    /// lanthanides and actinides sit in rows 8 and 9 below the main table,
+    /// starting under group 3.
+    pub fn table_position(&self) -> (usize, usize) {
+        let z = self.atomic_number();
+        match z {
+            1 => (0, 0),
+            2 => (0, 17),
+            3..=4 => (1, z - 3),
+            5..=10 => (1, z - 5 + 12),
+            11..=12 => (2, z - 11),
+            13..=18 => (2, z - 13 + 12),
+            19..=36 => (3, z - 19),
+            37..=54 => (4, z - 37),
+            55..=56 => (5, z - 55),
+            57..=71 => (8, z - 57 + 2),
+            72..=86 => (5, z - 72 + 3),
+            87..=88 => (6, z - 87),
+            89..=103 => (9, z - 89 + 2),
+            104..=118 => (6, z - 104 + 3),
+            // Hypothetical period 8
+            _ => (7, z - 119),
+        }
+    }
+
     pub fn symbol(&self) -> ElementSymbol {
         let string = self.to_string();
         ElementSymbol::from_str(&string)
@@ -1,8 +1,14 @@
 mod components;
 pub mod element;
 mod plugins;
+pub mod prefab;
+mod resources;
+mod ron_io;
+pub mod scenario;
+pub mod snapshot;
 mod systems;
 
 pub use components::*;
 pub use plugins::*;
+pub use resources::*;
 pub use systems::*;
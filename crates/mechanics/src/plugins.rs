@@ -1,24 +1,158 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, state::app::StatesPlugin};
 use bevy_dynamics::DynamicsPlugin;
 
 // use crate::{electromagnetic_interaction, gravity, strong_interaction};
+use crate::scenario::Scenario;
+use crate::snapshot::Snapshot;
 use crate::{
-    apply_forces, detect_atoms, maintain_atom_hitbox_colour, spawn_atom_hitbox,
+    advance_step, apply_forces, detect_atoms, hold_frozen,
+    record_stats_history, restore_snapshot, run_pending_steps, seed_rng,
+    spawn_scenario, sync_damping, sync_simulation_clock, update_stats,
+    PendingSteps, PhysicsConstants, SimStats, SimStep, SimulationState,
+    StatsHistory, TimeScale,
 };
 
 pub struct MechanicsPlugin;
 
 impl Plugin for MechanicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(DynamicsPlugin).add_systems(
-            Update,
-            (
-                apply_forces,
-                detect_atoms,
-                spawn_atom_hitbox,
-                maintain_atom_hitbox_colour,
-                // write_atom_info,
-            ),
+        // Headless runs only add `MinimalPlugins`, which lack states
+        if !app.is_plugin_added::<StatesPlugin>() {
+            app.add_plugins(StatesPlugin);
+        }
+
+        app.add_plugins(DynamicsPlugin)
+            .init_state::<SimulationState>()
+            .init_resource::<TimeScale>()
+            .init_resource::<PendingSteps>()
+            .init_resource::<Scenario>()
+            .init_resource::<PhysicsConstants>()
+            .init_resource::<SimStep>()
+            .init_resource::<SimStats>()
+            .init_resource::<StatsHistory>()
+            .add_systems(
+                Startup,
+                (
+                    seed_rng,
+                    (
+                        spawn_scenario.run_if(not(resource_exists::<Snapshot>)),
+                        restore_snapshot.run_if(resource_exists::<Snapshot>),
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    (sync_simulation_clock, run_pending_steps).chain(),
+                    sync_damping.run_if(resource_changed::<PhysicsConstants>),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    advance_step,
+                    apply_forces,
+                    hold_frozen,
+                    detect_atoms,
+                    update_stats,
+                    record_stats_history,
+                    // write_atom_info,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy_dynamics::Velocity;
+
+    use crate::particle::Particle;
+    use crate::scenario::{InitialVelocity, Scenario};
+    use crate::{
+        Kind, MechanicsPlugin, PendingSteps, SimRng, SimStats, SimStep,
+    };
+
+    /// A small scenario that draws positions and velocities from the rng
+    fn random_scenario() -> Scenario {
+        let mut scenario = Scenario::default();
+        for kind in [Kind::Proton, Kind::Neutron, Kind::Electron] {
+            scenario.set_count(kind, 8);
+        }
+        for group in &mut scenario.groups {
+            group.velocity = InitialVelocity::Random { speed: 10.0 };
+        }
+        scenario
+    }
+
+    /// Kind, position and velocity of every particle after startup
+    fn initial_state(
+        scenario: Scenario,
+        rng: Option<SimRng>,
+    ) -> Vec<(Kind, [f32; 3], [f32; 3])> {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, MechanicsPlugin))
+            .insert_resource(scenario);
+        if let Some(rng) = rng {
+            app.insert_resource(rng);
+        }
+        app.update();
+
+        app.world_mut()
+            .query::<(&Particle, &Transform, &Velocity)>()
+            .iter(app.world())
+            .map(|(particle, transform, velocity)| {
+                (
+                    particle.kind,
+                    transform.translation.to_array(),
+                    velocity.value.to_array(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn steps_without_a_window() {
+        let mut scenario = Scenario::default();
+        for kind in [Kind::Proton, Kind::Neutron, Kind::Electron] {
+            scenario.set_count(kind, 4);
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, MechanicsPlugin))
+            .insert_resource(scenario)
+            .insert_resource(SimRng::new(42))
+            .insert_resource(PendingSteps(10));
+        app.update();
+
+        assert_eq!(app.world().resource::<SimStep>().0, 10);
+        let stats = app.world().resource::<SimStats>();
+        for kind in [Kind::Proton, Kind::Neutron, Kind::Electron] {
+            assert_eq!(stats.particle_count(kind), 4);
+        }
+    }
+
+    #[test]
+    fn same_seed_spawns_the_same_particles() {
+        let first = initial_state(random_scenario(), Some(SimRng::new(42)));
+        let second = initial_state(random_scenario(), Some(SimRng::new(42)));
+        let other = initial_state(random_scenario(), Some(SimRng::new(43)));
+
+        assert_eq!(first.len(), 24);
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn scenario_seed_replays_like_a_given_seed() {
+        let mut seeded = random_scenario();
+        seeded.seed = Some(7);
+
+        assert_eq!(
+            initial_state(seeded, None),
+            initial_state(random_scenario(), Some(SimRng::new(7)))
         );
     }
 }
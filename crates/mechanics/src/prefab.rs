@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_dynamics::Velocity;
+use serde::{Deserialize, Serialize};
+
+use crate::snapshot::ParticleState;
+use crate::{ron_io, spawn_particle, Particle};
+
+/// A group of particles saved from a selection, written as RON. Positions
+/// are relative to the centre of the group, so it can be placed anywhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prefab {
+    pub particles: Vec<ParticleState>,
+}
+
+impl Prefab {
+    pub fn capture<'a>(
+        particles: impl Iterator<Item = (&'a Particle, &'a Transform, &'a Velocity)>,
+    ) -> Self {
+        let mut particles: Vec<ParticleState> = particles
+            .map(|(particle, transform, velocity)| ParticleState {
+                kind: particle.kind,
+                position: transform.translation.to_array(),
+                velocity: velocity.value.to_array(),
+            })
+            .collect();
+
+        let center = particles
+            .iter()
+            .map(|particle| Vec3::from_array(particle.position))
+            .sum::<Vec3>()
+            / particles.len().max(1) as f32;
+        for particle in &mut particles {
+            particle.position =
+                (Vec3::from_array(particle.position) - center).to_array();
+        }
+
+        Self { particles }
+    }
+
+    /// Spawns the group centred on `center`, returning the new particles
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        center: Vec3,
+        damping: f32,
+    ) -> Vec<Entity> {
+        self.particles
+            .iter()
+            .map(|particle| {
+                spawn_particle(
+                    commands,
+                    particle.kind,
+                    center + Vec3::from_array(particle.position),
+                    Vec3::from_array(particle.velocity),
+                    damping,
+                )
+            })
+            .collect()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        ron_io::load(path.as_ref(), "prefab")
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        ron_io::save(self, path.as_ref(), "prefab")
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+
+use bevy::prelude::*;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::element::Element;
+use crate::ron_io;
+use crate::{Kind, Particle};
+
+pub const GRAVITATIONAL_CONSTANT: f32 = 50000.0;
+pub const COULOMB_CONSTANT: f32 = 69000.0;
+pub const STRONG_FORCE_CONSTANT: f32 = 1000000.0;
+pub const RANGE_CONSTANT: f32 = 2.0;
+pub const EQUILIBRIUM_DISTANCE: f32 = 5.0;
+pub const NUCLEUS_FORMATION_DISTANCE: f32 = 12.0;
+pub const DAMPING: f32 = 2.0;
+
+/// The constants driving the force model and nucleus detection.
+///
+/// Missing fields fall back to their defaults when deserialized, so a
+/// scenario only has to list the values it wants to override.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsConstants {
+    pub gravitational_constant: f32,
+    pub coulomb_constant: f32,
+    pub strong_force_constant: f32,
+    pub range_constant: f32,
+    pub equilibrium_distance: f32,
+    pub nucleus_formation_distance: f32,
+    pub damping: f32,
+}
+
+impl Default for PhysicsConstants {
+    fn default() -> Self {
+        Self {
+            gravitational_constant: GRAVITATIONAL_CONSTANT,
+            coulomb_constant: COULOMB_CONSTANT,
+            strong_force_constant: STRONG_FORCE_CONSTANT,
+            range_constant: RANGE_CONSTANT,
+            equilibrium_distance: EQUILIBRIUM_DISTANCE,
+            nucleus_formation_distance: NUCLEUS_FORMATION_DISTANCE,
+            damping: DAMPING,
+        }
+    }
+}
+
+impl PhysicsConstants {
+    /// Potential energy a unit test particle would have at `distance` from
+    /// `source`, i.e. the integral of the force `apply_forces` computes.
+    ///
+    /// `distance` is clamped to the source radius to stay finite inside it.
+    pub fn potential(
+        &self,
+        field: FieldKind,
+        source: &Particle,
+        distance: f32,
+    ) -> f32 {
+        let r = distance.max(source.radius);
+        match field {
+            FieldKind::Electric => {
+                self.coulomb_constant * source.charge.charge() / r
+            }
+            FieldKind::Gravitational => {
+                -self.gravitational_constant * source.mass / r
+            }
+            FieldKind::Strong => {
+                let a = self.range_constant;
+                -self.strong_force_constant
+                    * (-a * r).exp()
+                    * ((r - self.equilibrium_distance) / a + 1.0 / (a * a))
+            }
+        }
+    }
+
+    /// Electric field of `source` at `offset` from it
+    pub fn electric_field(&self, source: &Particle, offset: Vec3) -> Vec3 {
+        let r = offset.length().max(source.radius);
+        offset.normalize_or_zero()
+            * self.coulomb_constant
+            * source.charge.charge()
+            / (r * r)
+    }
+
+    /// Reads a preset written by [`PhysicsConstants::save`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        ron_io::load(path.as_ref(), "preset")
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        ron_io::save(self, path.as_ref(), "preset")
+    }
+}
+
+/// One tunable field of [`PhysicsConstants`], so tools can read and write
+/// the constants without naming every field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum PhysicsParameter {
+    GravitationalConstant,
+    CoulombConstant,
+    StrongForceConstant,
+    RangeConstant,
+    EquilibriumDistance,
+    NucleusFormationDistance,
+    Damping,
+}
+
+impl PhysicsParameter {
+    /// Field name, as written in scenario and preset files
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::GravitationalConstant => "gravitational_constant",
+            Self::CoulombConstant => "coulomb_constant",
+            Self::StrongForceConstant => "strong_force_constant",
+            Self::RangeConstant => "range_constant",
+            Self::EquilibriumDistance => "equilibrium_distance",
+            Self::NucleusFormationDistance => "nucleus_formation_distance",
+            Self::Damping => "damping",
+        }
+    }
+
+    pub const fn get(self, physics: &PhysicsConstants) -> f32 {
+        match self {
+            Self::GravitationalConstant => physics.gravitational_constant,
+            Self::CoulombConstant => physics.coulomb_constant,
+            Self::StrongForceConstant => physics.strong_force_constant,
+            Self::RangeConstant => physics.range_constant,
+            Self::EquilibriumDistance => physics.equilibrium_distance,
+            Self::NucleusFormationDistance => {
+                physics.nucleus_formation_distance
+            }
+            Self::Damping => physics.damping,
+        }
+    }
+
+    /// Whether `value` makes sense for any constant: they are all finite,
+    /// non-negative magnitudes
+    pub const fn accepts(value: f32) -> bool {
+        value.is_finite() && value >= 0.0
+    }
+
+    pub fn set(self, physics: &mut PhysicsConstants, value: f32) {
+        let field = match self {
+            Self::GravitationalConstant => &mut physics.gravitational_constant,
+            Self::CoulombConstant => &mut physics.coulomb_constant,
+            Self::StrongForceConstant => &mut physics.strong_force_constant,
+            Self::RangeConstant => &mut physics.range_constant,
+            Self::EquilibriumDistance => &mut physics.equilibrium_distance,
+            Self::NucleusFormationDistance => {
+                &mut physics.nucleus_formation_distance
+            }
+            Self::Damping => &mut physics.damping,
+        };
+        *field = value;
+    }
+}
+
+/// The fields that make up the force model
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize,
+)]
+pub enum FieldKind {
+    Electric,
+    Gravitational,
+    Strong,
+}
+
+/// The single source of randomness for the simulation.
+///
+/// Everything that needs random numbers should draw from this resource
+/// instead of `rand::random()`, so a run can be replayed from its seed.
+#[derive(Resource, Debug, Clone)]
+pub struct SimRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn from_entropy() -> Self {
+        Self::new(rand::random())
+    }
+
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Whether fixed steps advance on their own.
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulationState {
+    #[default]
+    Running,
+    /// Virtual time is frozen, only [`PendingSteps`] advance the simulation
+    Paused,
+}
+
+/// Speed of virtual time relative to real time, and with it the rate of
+/// fixed steps.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Fixed steps to run on the next frame regardless of the
+/// [`SimulationState`], for stepping while paused.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct PendingSteps(pub u32);
+
+/// Number of fixed steps the simulation has advanced since startup.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SimStep(pub u64);
+
+/// Census of the current particles and atoms, refreshed every fixed step.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct SimStats {
+    pub particle_counts: HashMap<Kind, usize>,
+    /// Protons and neutrons that are not part of any atom
+    pub free_nucleons: usize,
+    pub atom_count: usize,
+    pub element_counts: BTreeMap<Element, usize>,
+    /// Highest count of each element seen at once during the run, so an
+    /// element stays discovered after its atoms break apart
+    pub peak_element_counts: BTreeMap<Element, usize>,
+    pub kinetic_energy: f32,
+    /// Pairwise potential energy of all three forces, refreshed only when
+    /// the [`StatsHistory`] takes a sample
+    pub potential_energy: f32,
+    /// Mean kinetic energy per particle times 2/3, i.e. with a Boltzmann
+    /// constant of one
+    pub temperature: f32,
+}
+
+impl SimStats {
+    pub fn particle_count(&self, kind: Kind) -> usize {
+        self.particle_counts.get(&kind).copied().unwrap_or_default()
+    }
+
+    pub fn total_energy(&self) -> f32 {
+        self.kinetic_energy + self.potential_energy
+    }
+}
+
+/// [`SimStats`] at one point in sim time
+#[derive(Debug, Clone)]
+pub struct StatsSample {
+    pub step: u64,
+    /// Seconds of sim time
+    pub time: f32,
+    pub total_energy: f32,
+    pub temperature: f32,
+    pub free_nucleons: usize,
+    pub atom_count: usize,
+    pub element_counts: BTreeMap<Element, usize>,
+}
+
+/// Rolling history of [`SimStats`], sampled every `interval` steps and
+/// dropping the oldest sample beyond `capacity`.
+#[derive(Resource, Debug, Clone)]
+pub struct StatsHistory {
+    pub samples: VecDeque<StatsSample>,
+    pub capacity: usize,
+    pub interval: u64,
+}
+
+impl Default for StatsHistory {
+    fn default() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: 600,
+            interval: 10,
+        }
+    }
+}
+
+impl StatsHistory {
+    pub fn push(&mut self, sample: StatsSample) {
+        while self.samples.len() >= self.capacity.max(1) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Reads a RON file, naming it as `noun` in errors
+pub fn load<T: DeserializeOwned>(path: &Path, noun: &str) -> Result<T, String> {
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        format!("Failed to read {noun} {}: {}", path.display(), err)
+    })?;
+    ron::from_str(&contents).map_err(|err| {
+        format!("Failed to parse {noun} {}: {}", path.display(), err)
+    })
+}
+
+/// Writes `value` as pretty RON, creating the parent directory if needed
+pub fn save<T: Serialize>(
+    value: &T,
+    path: &Path,
+    noun: &str,
+) -> Result<(), String> {
+    let contents =
+        ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+            .map_err(|err| format!("Failed to serialize {noun}: {err}"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| {
+            format!("Failed to create {}: {}", parent.display(), err)
+        })?;
+    }
+    std::fs::write(path, contents).map_err(|err| {
+        format!("Failed to write {noun} {}: {}", path.display(), err)
+    })
+}
@@ -0,0 +1,334 @@
+use std::f32::consts::TAU;
+use std::path::Path;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{ron_io, Kind, PhysicsConstants};
+
+/// Declarative description of the initial conditions of a run.
+///
+/// Scenarios are written in RON, e.g.
+///
+/// ```ron
+/// (
+///     groups: [
+///         (kind: Proton, count: 118, distribution: UniformBox(size: 300.0)),
+///         (kind: Neutron, count: 157, distribution: Sphere(radius: 150.0)),
+///     ],
+///     placements: [
+///         (kind: Electron, position: (0.0, 20.0, 0.0), velocity: Fixed((5.0, 0.0, 0.0))),
+///     ],
+///     nuclei: [
+///         (protons: 2, neutrons: 2, count: 10, distribution: Gaussian(std_dev: 60.0)),
+///     ],
+///     physics: (coulomb_constant: 50000.0),
+///     seed: Some(42),
+/// )
+/// ```
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub groups: Vec<ParticleGroup>,
+    #[serde(default)]
+    pub placements: Vec<Placement>,
+    #[serde(default)]
+    pub nuclei: Vec<NucleusGroup>,
+    #[serde(default)]
+    pub physics: PhysicsConstants,
+    /// Seed for the [`SimRng`](crate::SimRng), unless one is given on the
+    /// command line
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        ron_io::load(path.as_ref(), "scenario")
+    }
+
+    /// Replaces all groups of `kind` with a single group of `count`
+    /// particles, keeping the layout of the first group that was replaced.
+    pub fn set_count(&mut self, kind: Kind, count: usize) {
+        let template = self
+            .groups
+            .iter()
+            .find(|group| group.kind == kind)
+            .cloned()
+            .unwrap_or(ParticleGroup {
+                kind,
+                count,
+                center: [0.0; 3],
+                distribution: Distribution::UniformBox { size: 300.0 },
+                velocity: InitialVelocity::Zero,
+            });
+        self.groups.retain(|group| group.kind != kind);
+        self.groups.push(ParticleGroup { count, ..template });
+    }
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        const SPAWN_RADIUS: f32 = 300.0;
+
+        Self {
+            groups: vec![
+                ParticleGroup {
+                    kind: Kind::Proton,
+                    count: 118,
+                    center: [0.0; 3],
+                    distribution: Distribution::UniformBox {
+                        size: SPAWN_RADIUS,
+                    },
+                    velocity: InitialVelocity::Zero,
+                },
+                ParticleGroup {
+                    kind: Kind::Neutron,
+                    count: 157,
+                    center: [0.0; 3],
+                    distribution: Distribution::UniformBox {
+                        size: SPAWN_RADIUS,
+                    },
+                    velocity: InitialVelocity::Zero,
+                },
+            ],
+            placements: Vec::new(),
+            nuclei: Vec::new(),
+            physics: PhysicsConstants::default(),
+            seed: None,
+        }
+    }
+}
+
+/// A number of particles of one kind spread out by a [`Distribution`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticleGroup {
+    pub kind: Kind,
+    pub count: usize,
+    #[serde(default)]
+    pub center: [f32; 3],
+    pub distribution: Distribution,
+    #[serde(default)]
+    pub velocity: InitialVelocity,
+}
+
+/// A single particle at an explicit position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Placement {
+    pub kind: Kind,
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub velocity: InitialVelocity,
+}
+
+/// A number of pre-built nuclei, each spawned as a tight cluster of
+/// protons and neutrons that the strong force holds together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NucleusGroup {
+    pub protons: usize,
+    pub neutrons: usize,
+    pub count: usize,
+    #[serde(default)]
+    pub center: [f32; 3],
+    pub distribution: Distribution,
+    #[serde(default)]
+    pub velocity: InitialVelocity,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Distribution {
+    /// Uniform within an axis aligned cube with the given side length
+    UniformBox { size: f32 },
+    /// Uniform within a ball with the given radius
+    Sphere { radius: f32 },
+    /// Normally distributed around the center on every axis
+    Gaussian { std_dev: f32 },
+    /// Simple cubic lattice, filled row by row from one corner
+    Lattice { spacing: f32 },
+}
+
+impl Distribution {
+    /// Position of the `index`th of `count` samples, relative to the center
+    /// of the group.
+    pub fn sample(
+        &self,
+        index: usize,
+        count: usize,
+        rng: &mut impl Rng,
+    ) -> Vec3 {
+        match *self {
+            Self::UniformBox { size } => {
+                Vec3::new(rng.gen(), rng.gen(), rng.gen()) * size
+                    - Vec3::splat(size / 2.0)
+            }
+            Self::Sphere { radius } => {
+                // The cube root keeps the density uniform over the volume
+                random_direction(rng) * radius * rng.gen::<f32>().cbrt()
+            }
+            Self::Gaussian { std_dev } => {
+                Vec3::new(
+                    standard_normal(rng),
+                    standard_normal(rng),
+                    standard_normal(rng),
+                ) * std_dev
+            }
+            Self::Lattice { spacing } => {
+                let side = (count as f32).cbrt().ceil().max(1.0) as usize;
+                let cell = Vec3::new(
+                    (index % side) as f32,
+                    (index / side % side) as f32,
+                    (index / (side * side)) as f32,
+                );
+                (cell - Vec3::splat((side - 1) as f32 / 2.0)) * spacing
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum InitialVelocity {
+    #[default]
+    Zero,
+    /// The same velocity for every particle
+    Fixed([f32; 3]),
+    /// A fixed speed in a uniformly random direction
+    Random { speed: f32 },
+}
+
+impl InitialVelocity {
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec3 {
+        match *self {
+            Self::Zero => Vec3::ZERO,
+            Self::Fixed(velocity) => Vec3::from_array(velocity),
+            Self::Random { speed } => random_direction(rng) * speed,
+        }
+    }
+}
+
+pub fn random_direction(rng: &mut impl Rng) -> Vec3 {
+    let z = rng.gen_range(-1.0..=1.0_f32);
+    let theta = rng.gen_range(0.0..TAU);
+    let r = (1.0 - z * z).sqrt();
+    Vec3::new(r * theta.cos(), r * theta.sin(), z)
+}
+
+/// Box-Muller transform, so we don't need `rand_distr` for a single normal
+pub fn standard_normal(rng: &mut impl Rng) -> f32 {
+    let u1 = rng.gen::<f32>().max(f32::MIN_POSITIVE);
+    let u2 = rng.gen::<f32>();
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Distribution, InitialVelocity, Scenario};
+    use crate::{Kind, PhysicsConstants};
+
+    #[test]
+    fn parses_every_section() -> Result<(), ron::error::SpannedError> {
+        let scenario: Scenario = ron::from_str(
+            "(
+                groups: [
+                    (kind: Proton, count: 118, distribution: UniformBox(size: 300.0)),
+                ],
+                placements: [
+                    (kind: Electron, position: (0.0, 20.0, 0.0), velocity: Fixed((5.0, 0.0, 0.0))),
+                ],
+                nuclei: [
+                    (protons: 2, neutrons: 2, count: 10, distribution: Gaussian(std_dev: 60.0)),
+                ],
+                physics: (coulomb_constant: 50000.0),
+                seed: Some(42),
+            )",
+        )?;
+
+        assert_eq!(scenario.groups.len(), 1);
+        assert_eq!(scenario.groups[0].kind, Kind::Proton);
+        assert_eq!(scenario.groups[0].count, 118);
+        assert_eq!(scenario.placements[0].position, [0.0, 20.0, 0.0]);
+        let InitialVelocity::Fixed(velocity) = scenario.placements[0].velocity
+        else {
+            panic!("expected a fixed velocity");
+        };
+        assert_eq!(velocity, [5.0, 0.0, 0.0]);
+        assert_eq!(scenario.nuclei[0].protons, 2);
+        assert_eq!(scenario.nuclei[0].count, 10);
+        assert_eq!(
+            scenario.physics,
+            PhysicsConstants {
+                coulomb_constant: 50000.0,
+                ..PhysicsConstants::default()
+            }
+        );
+        assert_eq!(scenario.seed, Some(42));
+        Ok(())
+    }
+
+    #[test]
+    fn defaults_missing_sections() -> Result<(), ron::error::SpannedError> {
+        let scenario: Scenario = ron::from_str("()")?;
+
+        assert!(scenario.groups.is_empty());
+        assert!(scenario.placements.is_empty());
+        assert!(scenario.nuclei.is_empty());
+        assert_eq!(scenario.physics, PhysicsConstants::default());
+        assert_eq!(scenario.seed, None);
+        Ok(())
+    }
+
+    #[test]
+    fn set_count_merges_groups() -> Result<(), ron::error::SpannedError> {
+        let mut scenario: Scenario = ron::from_str(
+            "(groups: [
+                (kind: Proton, count: 5, center: (1.0, 2.0, 3.0), distribution: Lattice(spacing: 4.0)),
+                (kind: Neutron, count: 7, distribution: Sphere(radius: 10.0)),
+                (kind: Proton, count: 9, distribution: Sphere(radius: 10.0)),
+            ])",
+        )?;
+        scenario.set_count(Kind::Proton, 20);
+
+        let protons: Vec<_> = scenario
+            .groups
+            .iter()
+            .filter(|group| group.kind == Kind::Proton)
+            .collect();
+        assert_eq!(protons.len(), 1);
+        assert_eq!(protons[0].count, 20);
+        // The layout of the first proton group is kept
+        assert_eq!(protons[0].center, [1.0, 2.0, 3.0]);
+        let Distribution::Lattice { spacing } = protons[0].distribution else {
+            panic!("expected the lattice of the first group");
+        };
+        assert_eq!(spacing, 4.0);
+        // Other kinds are left alone
+        let neutrons: Vec<_> = scenario
+            .groups
+            .iter()
+            .filter(|group| group.kind == Kind::Neutron)
+            .collect();
+        assert_eq!(neutrons.len(), 1);
+        assert_eq!(neutrons[0].count, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn set_count_adds_a_missing_kind() {
+        let mut scenario = Scenario::default();
+        scenario.set_count(Kind::Electron, 3);
+
+        let Some(electrons) = scenario
+            .groups
+            .iter()
+            .find(|group| group.kind == Kind::Electron)
+        else {
+            panic!("no electron group was added");
+        };
+        assert_eq!(electrons.count, 3);
+        assert!(matches!(
+            electrons.distribution,
+            Distribution::UniformBox { .. }
+        ));
+        assert_eq!(scenario.groups.len(), 3);
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_dynamics::Velocity;
+use serde::{Deserialize, Serialize};
+
+use crate::{ron_io, Kind, Particle, PhysicsConstants};
+
+/// The full dynamic state of a run at one fixed step, written as RON.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub step: u64,
+    pub seed: u64,
+    pub physics: PhysicsConstants,
+    pub particles: Vec<ParticleState>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ParticleState {
+    pub kind: Kind,
+    pub position: [f32; 3],
+    pub velocity: [f32; 3],
+}
+
+impl Snapshot {
+    pub fn capture<'a>(
+        step: u64,
+        seed: u64,
+        physics: PhysicsConstants,
+        particles: impl Iterator<Item = (&'a Particle, &'a Transform, &'a Velocity)>,
+    ) -> Self {
+        Self {
+            step,
+            seed,
+            physics,
+            particles: particles
+                .map(|(particle, transform, velocity)| ParticleState {
+                    kind: particle.kind,
+                    position: transform.translation.to_array(),
+                    velocity: velocity.value.to_array(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        ron_io::load(path.as_ref(), "snapshot")
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        ron_io::save(self, path.as_ref(), "snapshot")
+    }
+}
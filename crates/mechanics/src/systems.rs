@@ -1,37 +1,243 @@
 use bevy::prelude::*;
-use bevy_dynamics::Acceleration;
-use std::collections::HashMap;
+use bevy_dynamics::{Acceleration, Damping, Debug, Velocity};
+use std::collections::{HashMap, HashSet};
 use strum::IntoEnumIterator;
 
 use crate::element::Element;
 use crate::particle::Particle;
-use crate::{Atom, AtomHitbox, Kind};
-
-const GRAVITATIONAL_CONSTANT: f32 = 50000.0;
-const COULOMB_CONSTANT: f32 = 69000.0;
-const STRONG_FORCE_CONSTANT: f32 = 1000000.0;
-const RANGE_CONSTANT: f32 = 2.0;
-const EQUILIBRIUM_DISTANCE: f32 = 5.0;
-const UNSELECTED_ALPHA: f32 = 20.0 / 255.0;
-const SELECTED_ALPHA: f32 = 100.0 / 255.0;
+use crate::scenario::{Distribution, Scenario};
+use crate::snapshot::Snapshot;
+use crate::{
+    Atom, ExternalForce, FieldKind, Forces, FormedAt, Frozen, Kind,
+    PendingSteps, PhysicsConstants, Selected, SimRng, SimStats, SimStep,
+    SimulationState, StatsHistory, StatsSample, TimeScale,
+};
+
 // const MAX_FORCE: f32 = 1000.0;
 
-// Helper struct to store accumulated forces
-#[derive(Default)]
-struct ForceAccumulator {
-    gravity: Vec3,
-    electromagnetic: Vec3,
-    strong: Vec3,
+/// Seeds the [`SimRng`], preferring one already inserted (e.g. from the
+/// command line) over the seed of a restored snapshot or the scenario, and
+/// logs the seed so the run can be replayed.
+pub fn seed_rng(
+    mut commands: Commands,
+    scenario: Res<Scenario>,
+    snapshot: Option<Res<Snapshot>>,
+    rng: Option<Res<SimRng>>,
+) {
+    let snapshot_seed = snapshot.map(|snapshot| snapshot.seed);
+    let seed = match (rng, snapshot_seed.or(scenario.seed)) {
+        (Some(rng), _) => rng.seed(),
+        (None, Some(seed)) => {
+            commands.insert_resource(SimRng::new(seed));
+            seed
+        }
+        (None, None) => {
+            let rng = SimRng::from_entropy();
+            let seed = rng.seed();
+            commands.insert_resource(rng);
+            seed
+        }
+    };
+
+    info!("Simulation seed: {seed} (replay with `--seed {seed}`)");
+}
+
+pub fn spawn_scenario(
+    mut commands: Commands,
+    scenario: Res<Scenario>,
+    mut rng: ResMut<SimRng>,
+) {
+    let rng = rng.as_mut();
+    let physics = scenario.physics;
+    commands.insert_resource(physics);
+
+    for group in &scenario.groups {
+        let center = Vec3::from_array(group.center);
+        for i in 0..group.count {
+            let position =
+                center + group.distribution.sample(i, group.count, rng);
+            let velocity = group.velocity.sample(rng);
+            spawn_particle(
+                &mut commands,
+                group.kind,
+                position,
+                velocity,
+                physics.damping,
+            );
+        }
+    }
+
+    for placement in &scenario.placements {
+        let velocity = placement.velocity.sample(rng);
+        spawn_particle(
+            &mut commands,
+            placement.kind,
+            Vec3::from_array(placement.position),
+            velocity,
+            physics.damping,
+        );
+    }
+
+    for nuclei in &scenario.nuclei {
+        let center = Vec3::from_array(nuclei.center);
+        // Keep the nucleons inside the equilibrium distance of each other so
+        // the strong force binds them from the first step
+        let nucleus_radius = physics.equilibrium_distance
+            * 0.5
+            * ((nuclei.protons + nuclei.neutrons) as f32).cbrt();
+        let nucleons = Distribution::Sphere {
+            radius: nucleus_radius,
+        };
+        let kinds = std::iter::repeat(Kind::Proton)
+            .take(nuclei.protons)
+            .chain(std::iter::repeat(Kind::Neutron).take(nuclei.neutrons));
+
+        for i in 0..nuclei.count {
+            let nucleus_center =
+                center + nuclei.distribution.sample(i, nuclei.count, rng);
+            let velocity = nuclei.velocity.sample(rng);
+            for kind in kinds.clone() {
+                let offset = nucleons.sample(0, 1, rng);
+                spawn_particle(
+                    &mut commands,
+                    kind,
+                    nucleus_center + offset,
+                    velocity,
+                    physics.damping,
+                );
+            }
+        }
+    }
+
+    info!(
+        "Spawned scenario: {} groups, {} placements, {} nucleus groups",
+        scenario.groups.len(),
+        scenario.placements.len(),
+        scenario.nuclei.len()
+    );
+}
+
+/// Spawns the particles of a [`Snapshot`] instead of the scenario, and
+/// continues counting steps from where the snapshot was taken.
+pub fn restore_snapshot(mut commands: Commands, snapshot: Res<Snapshot>) {
+    commands.insert_resource(snapshot.physics);
+    commands.insert_resource(SimStep(snapshot.step));
+
+    for particle in &snapshot.particles {
+        spawn_particle(
+            &mut commands,
+            particle.kind,
+            Vec3::from_array(particle.position),
+            Vec3::from_array(particle.velocity),
+            snapshot.physics.damping,
+        );
+    }
+
+    info!(
+        "Restored {} particles from snapshot at step {}",
+        snapshot.particles.len(),
+        snapshot.step
+    );
+}
+
+pub fn spawn_particle(
+    commands: &mut Commands,
+    kind: Kind,
+    position: Vec3,
+    velocity: Vec3,
+    damping: f32,
+) -> Entity {
+    let mut entity = commands.spawn((
+        Particle::from_kind(kind),
+        Transform::from_translation(position),
+        Velocity { value: velocity },
+        Damping::new(damping),
+    ));
+
+    if matches!(kind, Kind::Proton | Kind::Neutron) {
+        entity.insert(Debug {
+            acceleration: false,
+            velocity: false,
+        });
+    }
+
+    entity.id()
+}
+
+/// Carries a changed [`PhysicsConstants::damping`] over to the particles,
+/// which only copy it when spawned
+pub fn sync_damping(
+    mut commands: Commands,
+    physics: Res<PhysicsConstants>,
+    particles: Query<Entity, With<Particle>>,
+    mut last: Local<Option<f32>>,
+) {
+    if *last == Some(physics.damping) {
+        return;
+    }
+    // Particles spawned with the first value already have it
+    if last.is_some() {
+        for entity in particles.iter() {
+            commands
+                .entity(entity)
+                .insert(Damping::new(physics.damping));
+        }
+    }
+    *last = Some(physics.damping);
+}
+
+/// Pauses and scales virtual time, which drives the fixed schedule, to
+/// follow the [`SimulationState`] and [`TimeScale`]
+pub fn sync_simulation_clock(
+    state: Res<State<SimulationState>>,
+    scale: Res<TimeScale>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if !state.is_changed() && !scale.is_changed() {
+        return;
+    }
+    match state.get() {
+        SimulationState::Running => time.unpause(),
+        SimulationState::Paused => time.pause(),
+    }
+    time.set_relative_speed(scale.0);
+}
+
+/// Runs the [`PendingSteps`] back to back, the same way the fixed main loop
+/// would, so stepping works while virtual time is paused
+pub fn run_pending_steps(world: &mut World) {
+    let steps = std::mem::take(&mut world.resource_mut::<PendingSteps>().0);
+    if steps == 0 {
+        return;
+    }
+
+    let timestep = world.resource::<Time<Fixed>>().timestep();
+    for _ in 0..steps {
+        world.resource_mut::<Time<Fixed>>().advance_by(timestep);
+        *world.resource_mut::<Time>() =
+            world.resource::<Time<Fixed>>().as_generic();
+        world.run_schedule(FixedMain);
+    }
+    *world.resource_mut::<Time>() =
+        world.resource::<Time<Virtual>>().as_generic();
 }
 
 pub fn apply_forces(
-    mut query: Query<(Entity, &Transform, &mut Acceleration, &Particle)>,
+    mut query: Query<(
+        Entity,
+        &Transform,
+        &mut Acceleration,
+        &Particle,
+        &mut Forces,
+        Option<&ExternalForce>,
+    )>,
+    physics: Res<PhysicsConstants>,
     time: Res<Time>,
 ) {
-    let mut force_map: HashMap<Entity, ForceAccumulator> = HashMap::new();
+    let mut force_map: HashMap<Entity, Forces> = HashMap::new();
 
     // Calculate gravitational forces
-    for [(entity_a, transform_a, _acceleration_a, particle_a), (entity_b, transform_b, _acceleration_b, particle_b)] in
+    for [(entity_a, transform_a, _acceleration_a, particle_a, _forces_a, _), (entity_b, transform_b, _acceleration_b, particle_b, _forces_b, _)] in
         query.iter_combinations()
     {
         let delta = transform_b.translation - transform_a.translation;
@@ -40,26 +246,26 @@ pub fn apply_forces(
 
         // Gravitational force
         let grav_force =
-            GRAVITATIONAL_CONSTANT * particle_a.mass * particle_b.mass
+            physics.gravitational_constant * particle_a.mass * particle_b.mass
                 / distance.powi(2);
         let grav_change = direction * grav_force;
 
         // Electromagnetic force
-        let em_force = -COULOMB_CONSTANT
+        let em_force = -physics.coulomb_constant
             * particle_a.charge.charge()
             * particle_b.charge.charge()
             / distance.powi(2);
         let em_change = direction * em_force;
 
         // Strong force
-        let force = (-distance * RANGE_CONSTANT).exp()
-            * (distance - EQUILIBRIUM_DISTANCE);
-        let strong_force = if distance < EQUILIBRIUM_DISTANCE {
+        let force = (-distance * physics.range_constant).exp()
+            * (distance - physics.equilibrium_distance);
+        let strong_force = if distance < physics.equilibrium_distance {
             // Apply damping factor only during repulsion (when particles are too close)
-            STRONG_FORCE_CONSTANT * force * 1.0 // Adjust this damping factor as needed
+            physics.strong_force_constant * force * 1.0 // Adjust this damping factor as needed
         } else {
             // Normal attractive force when particles are further than equilibrium
-            STRONG_FORCE_CONSTANT * force
+            physics.strong_force_constant * force
         };
         let strong_change = direction * strong_force;
 
@@ -75,10 +281,22 @@ pub fn apply_forces(
     }
 
     // Apply accumulated forces
-    for (entity, _transform, mut acceleration, particle) in query.iter_mut() {
+    for (
+        entity,
+        _transform,
+        mut acceleration,
+        particle,
+        mut particle_forces,
+        external,
+    ) in query.iter_mut()
+    {
         if let Some(forces) = force_map.get(&entity) {
-            let total_force =
-                forces.gravity + forces.electromagnetic + forces.strong;
+            *particle_forces = Forces {
+                external: external.map_or(Vec3::ZERO, |external| external.0),
+                ..*forces
+            };
+            let forces = *particle_forces;
+            let total_force = forces.total();
             let change = total_force * time.delta_secs();
 
             // TODO: Handle this better by applying acceleration from the force -> then resulting in a velocity
@@ -95,13 +313,13 @@ pub fn apply_forces(
     }
 }
 
-const NUCLEUS_FORMATION_DISTANCE: f32 = 12.0;
-
 pub fn detect_atoms(
     mut commands: Commands,
+    physics: Res<PhysicsConstants>,
+    step: Res<SimStep>,
     particle_query: Query<(Entity, &Transform, &Particle)>,
     mut atoms: Query<
-        (Entity, &mut Atom, &mut Transform, &mut AtomHitbox),
+        (Entity, &mut Atom, &mut Transform, Has<Selected>),
         Without<Particle>,
     >,
 ) {
@@ -129,7 +347,9 @@ pub fn detect_atoms(
                 .unwrap()
                 / cluster.len() as f32;
 
-            if (pos_a - cluster_center).length() < NUCLEUS_FORMATION_DISTANCE {
+            if (pos_a - cluster_center).length()
+                < physics.nucleus_formation_distance
+            {
                 cluster.push((entity_a, pos_a, particle_a));
                 found_cluster = true;
                 break;
@@ -182,8 +402,8 @@ pub fn detect_atoms(
         let mut should_be_selected = false;
 
         // First check if any of the constituent particles were part of a selected atom
-        for (_, atom, _, hitbox) in atoms.iter() {
-            if hitbox.selected
+        for (_, atom, _, selected) in atoms.iter() {
+            if selected
                 && atom
                     .constituent_particles
                     .iter()
@@ -195,8 +415,7 @@ pub fn detect_atoms(
         }
 
         // Now update or create the atom with the correct selected state
-        for (atom_entity, mut atom, mut transform, mut hitbox) in
-            atoms.iter_mut()
+        for (atom_entity, mut atom, mut transform, selected) in atoms.iter_mut()
         {
             if atom
                 .constituent_particles
@@ -213,7 +432,12 @@ pub fn detect_atoms(
                     *atom = new_atom;
                     transform.translation = nucleus_center;
                     transform.scale = Vec3::splat(atom.radius());
-                    hitbox.selected = should_be_selected; // Set the correct selected state
+                    // Set the correct selected state
+                    if should_be_selected && !selected {
+                        commands.entity(atom_entity).insert(Selected);
+                    } else if !should_be_selected && selected {
+                        commands.entity(atom_entity).remove::<Selected>();
+                    }
                     active_atoms.insert(atom_entity);
                     updated_existing = true;
                     break;
@@ -228,170 +452,135 @@ pub fn detect_atoms(
             {
                 let transform = Transform::from_translation(nucleus_center)
                     .with_scale(Vec3::splat(atom.radius()));
-                let entity = commands
-                    .spawn((
-                        atom,
-                        transform,
-                        AtomHitbox {
-                            selected: should_be_selected,
-                        }, // Set the correct selected state
-                    ))
-                    .id();
+                let mut entity = commands.spawn((
+                    atom,
+                    transform,
+                    FormedAt { step: step.0 },
+                ));
+                // Set the correct selected state
+                if should_be_selected {
+                    entity.insert(Selected);
+                }
+                let entity = entity.id();
                 active_atoms.insert(entity);
             }
         }
     }
 
     // Remove atoms that are no longer valid
-    for (entity, _atom, _transform, _hitbox) in atoms.iter() {
+    for (entity, _atom, _transform, _selected) in atoms.iter() {
         if !active_atoms.contains(&entity) {
             commands.entity(entity).despawn_recursive();
         }
     }
 }
 
-pub fn maintain_atom_hitbox_colour(
-    mut query: Query<
-        (&Atom, &mut MeshMaterial3d<StandardMaterial>, &AtomHitbox),
-        Changed<Atom>,
-    >,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+/// Undoes whatever [`apply_forces`] and the last integration did to
+/// [`Frozen`] particles
+pub fn hold_frozen(
+    mut particles: Query<(&mut Velocity, &mut Acceleration), With<Frozen>>,
 ) {
-    for (atom, material, hitbox) in query.iter_mut() {
-        if let Some(material) = materials.get_mut(&material.0) {
-            let alpha = if hitbox.selected {
-                SELECTED_ALPHA
-            } else {
-                UNSELECTED_ALPHA
-            };
-            material.base_color = atom.element.color().with_alpha(alpha);
-        }
+    for (mut velocity, mut acceleration) in particles.iter_mut() {
+        velocity.value = Vec3::ZERO;
+        acceleration.value = Vec3::ZERO;
     }
 }
 
-pub fn spawn_atom_hitbox(
-    mut commands: Commands,
-    query: Query<(Entity, &Atom, &AtomHitbox), Added<Atom>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    for (entity, atom, hitbox) in query.iter() {
-        commands
-            .entity(entity)
-            .insert((
-                Mesh3d(meshes.add(Sphere::new(1.0).mesh().ico(10).unwrap())),
-                MeshMaterial3d(materials.add(StandardMaterial {
-                    base_color: match hitbox.selected {
-                        true => atom.element.color().with_alpha(SELECTED_ALPHA),
-                        false => {
-                            atom.element.color().with_alpha(UNSELECTED_ALPHA)
-                        }
-                    },
-                    alpha_mode: AlphaMode::Blend,
-                    ..default()
-                })),
-            ))
-            .observe(over_atom)
-            .observe(out_atom)
-            .observe(click_atom);
-    }
+pub fn advance_step(mut step: ResMut<SimStep>) {
+    step.0 += 1;
 }
 
-fn over_atom(
-    trigger: Trigger<Pointer<Over>>,
-    mut query: Query<(&mut MeshMaterial3d<StandardMaterial>, &AtomHitbox)>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+pub fn update_stats(
+    mut stats: ResMut<SimStats>,
+    particles: Query<(Entity, &Particle, &Velocity)>,
+    atoms: Query<&Atom>,
 ) {
-    if let Ok((material, hitbox)) = query.get_mut(trigger.entity()) {
-        if hitbox.selected {
-            return;
-        }
-
-        if let Some(material) = materials.get_mut(&material.0) {
-            material.base_color.set_alpha(SELECTED_ALPHA);
+    let bound: HashSet<Entity> = atoms
+        .iter()
+        .flat_map(|atom| atom.constituent_particles.iter().copied())
+        .collect();
+
+    stats.particle_counts.clear();
+    stats.free_nucleons = 0;
+    stats.kinetic_energy = 0.0;
+    for (entity, particle, velocity) in particles.iter() {
+        *stats.particle_counts.entry(particle.kind).or_default() += 1;
+        stats.kinetic_energy +=
+            0.5 * particle.mass * velocity.value.length_squared();
+        if matches!(particle.kind, Kind::Proton | Kind::Neutron)
+            && !bound.contains(&entity)
+        {
+            stats.free_nucleons += 1;
         }
     }
-}
 
-fn out_atom(
-    trigger: Trigger<Pointer<Out>>,
-    mut query: Query<(&mut MeshMaterial3d<StandardMaterial>, &AtomHitbox)>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    if let Ok((material, hitbox)) = query.get_mut(trigger.entity()) {
-        if hitbox.selected {
-            return;
-        }
+    let particle_count = particles.iter().len();
+    stats.temperature = if particle_count > 0 {
+        2.0 / 3.0 * stats.kinetic_energy / particle_count as f32
+    } else {
+        0.0
+    };
+
+    stats.atom_count = 0;
+    stats.element_counts.clear();
+    for atom in atoms.iter() {
+        stats.atom_count += 1;
+        *stats.element_counts.entry(atom.element).or_default() += 1;
+    }
 
-        if let Some(material) = materials.get_mut(&material.0) {
-            material.base_color.set_alpha(UNSELECTED_ALPHA);
-        }
+    let SimStats {
+        element_counts,
+        peak_element_counts,
+        ..
+    } = &mut *stats;
+    for (element, count) in element_counts.iter() {
+        let peak = peak_element_counts.entry(*element).or_default();
+        *peak = (*peak).max(*count);
     }
 }
 
-fn click_atom(
-    trigger: Trigger<Pointer<Click>>,
-    mut query: Query<(
-        Entity,
-        &mut MeshMaterial3d<StandardMaterial>,
-        &mut AtomHitbox,
-        &Atom,
-    )>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+/// Appends the current [`SimStats`] to the [`StatsHistory`] every
+/// `interval` steps, along with the potential energy, which is only worth
+/// its pairwise loop when sampled
+pub fn record_stats_history(
+    mut history: ResMut<StatsHistory>,
+    mut stats: ResMut<SimStats>,
+    step: Res<SimStep>,
+    time: Res<Time>,
+    particles: Query<(&Particle, &Transform)>,
+    physics: Res<PhysicsConstants>,
 ) {
-    // only if left click
-    if trigger.button != PointerButton::Primary {
+    if step.0 % history.interval.max(1) != 0 {
         return;
     }
 
-    if let Ok((entity, material_handle, mut hitbox, atom)) =
-        query.get_mut(trigger.entity())
+    stats.potential_energy = 0.0;
+    for [(particle_a, transform_a), (particle_b, transform_b)] in
+        particles.iter_combinations()
     {
-        hitbox.selected = !hitbox.selected;
-
-        if let Some(material) = materials.get_mut(&material_handle.0) {
-            match hitbox.selected {
-                true => {
-                    material.base_color =
-                        atom.element.color().with_alpha(SELECTED_ALPHA);
-                    // deselect all other atoms
-                    for (
-                        other_entity,
-                        other_material_handle,
-                        mut other_hitbox,
-                        other_atom,
-                    ) in query.iter_mut()
-                    {
-                        if other_entity == entity {
-                            continue;
-                        }
-                        if other_hitbox.selected {
-                            other_hitbox.selected = false;
-                            if let Some(material) =
-                                materials.get_mut(&other_material_handle.0)
-                            {
-                                material.base_color = other_atom
-                                    .element
-                                    .color()
-                                    .with_alpha(UNSELECTED_ALPHA);
-                            }
-                        }
-                    }
-                }
-                false => {
-                    material.base_color =
-                        atom.element.color().with_alpha(UNSELECTED_ALPHA);
-                }
-            }
-        }
+        let distance =
+            transform_a.translation.distance(transform_b.translation);
+        stats.potential_energy +=
+            physics.potential(FieldKind::Gravitational, particle_b, distance)
+                * particle_a.mass
+                + physics.potential(FieldKind::Electric, particle_b, distance)
+                    * particle_a.charge.charge()
+                + physics.potential(FieldKind::Strong, particle_b, distance);
     }
+    history.push(StatsSample {
+        step: step.0,
+        time: time.elapsed_secs(),
+        total_energy: stats.total_energy(),
+        temperature: stats.temperature,
+        free_nucleons: stats.free_nucleons,
+        atom_count: stats.atom_count,
+        element_counts: stats.element_counts.clone(),
+    });
 }
 
 // system that writes information about the currently selected atom to the UI
-pub fn write_atom_info(query: Query<(&Atom, &AtomHitbox)>) {
-    for (atom, hitbox) in query.iter() {
-        if hitbox.selected {
-            info!("Selected atom: {}", atom);
-        }
+pub fn write_atom_info(query: Query<&Atom, With<Selected>>) {
+    for atom in query.iter() {
+        info!("Selected atom: {}", atom);
     }
 }
@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+    time::TimeUpdateStrategy,
+};
+use mechanics::SimStep;
+
+/// Where captured frames go.
+#[derive(Debug, Clone)]
+pub enum CaptureOutput {
+    /// Numbered PNGs in this directory
+    Frames(PathBuf),
+    /// Raw RGBA frames written to the stdin of this encoder command
+    Pipe(String),
+}
+
+/// Where captured frames are written, opened in `main` so a bad directory
+/// or encoder command fails before the app starts
+#[derive(Resource)]
+pub enum CaptureSink {
+    Frames(PathBuf),
+    Encoder(Child),
+}
+
+impl CaptureSink {
+    pub fn open(output: &CaptureOutput) -> Result<Self, String> {
+        match output {
+            CaptureOutput::Frames(dir) => {
+                std::fs::create_dir_all(dir).map_err(|err| {
+                    format!(
+                        "Failed to create capture directory {}: {}",
+                        dir.display(),
+                        err
+                    )
+                })?;
+                Ok(Self::Frames(dir.clone()))
+            }
+            CaptureOutput::Pipe(command) => {
+                let mut args = command.split_whitespace();
+                let program = args
+                    .next()
+                    .ok_or_else(|| "Empty capture pipe command".to_string())?;
+                Command::new(program)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .spawn()
+                    .map(Self::Encoder)
+                    .map_err(|err| {
+                        format!("Failed to start encoder `{command}`: {err}")
+                    })
+            }
+        }
+    }
+}
+
+/// Renders one frame per fixed step between `start_step` and `end_step`,
+/// advancing time by exactly one step per frame so the video plays
+/// smoothly no matter how slow the simulation runs. Expects a
+/// [`CaptureSink`] to write to.
+pub struct CapturePlugin {
+    pub start_step: u64,
+    /// Exit once this step has been written, capture until closed if `None`
+    pub end_step: Option<u64>,
+}
+
+/// Marks a pending screenshot with the step it shows and its place in the
+/// captured sequence
+#[derive(Component)]
+struct CaptureFrame {
+    step: u64,
+    sequence: u64,
+}
+
+#[derive(Resource)]
+struct CaptureRun {
+    start_step: u64,
+    end_step: Option<u64>,
+    last_requested: Option<u64>,
+    in_flight: usize,
+    /// Sequence number of the next requested frame
+    next_sequence: u64,
+    /// Screenshots may arrive out of order, but the encoder needs them in
+    /// order, so early ones wait here by sequence number. Steps can't be
+    /// used, as a frame may advance more than one.
+    pending: BTreeMap<u64, Vec<u8>>,
+    next_piped: u64,
+}
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep))
+            .insert_resource(CaptureRun {
+                start_step: self.start_step,
+                end_step: self.end_step,
+                last_requested: None,
+                in_flight: 0,
+                next_sequence: 0,
+                pending: BTreeMap::new(),
+                next_piped: 0,
+            })
+            .add_systems(Last, (request_frame, stop_when_captured).chain());
+    }
+}
+
+fn request_frame(
+    mut commands: Commands,
+    mut run: ResMut<CaptureRun>,
+    step: Res<SimStep>,
+) {
+    let step = step.0;
+    let in_range =
+        step >= run.start_step && run.end_step.is_none_or(|end| step <= end);
+    if !in_range || run.last_requested == Some(step) {
+        return;
+    }
+
+    let sequence = run.next_sequence;
+    run.next_sequence += 1;
+    run.last_requested = Some(step);
+    run.in_flight += 1;
+    commands
+        .spawn((
+            Screenshot::primary_window(),
+            CaptureFrame { step, sequence },
+        ))
+        .observe(write_frame);
+}
+
+fn write_frame(
+    trigger: Trigger<ScreenshotCaptured>,
+    frames: Query<&CaptureFrame>,
+    mut run: ResMut<CaptureRun>,
+    mut sink: ResMut<CaptureSink>,
+) {
+    let Ok(frame) = frames.get(trigger.entity()) else {
+        return;
+    };
+    run.in_flight -= 1;
+
+    let image = match trigger.event().0.clone().try_into_dynamic() {
+        Ok(image) => Some(image),
+        Err(err) => {
+            error!("Failed to convert frame {}: {err}", frame.step);
+            None
+        }
+    };
+
+    match sink.as_mut() {
+        CaptureSink::Frames(dir) => {
+            let Some(image) = image else {
+                return;
+            };
+            let path = dir.join(format!("frame_{:08}.png", frame.step));
+            // The alpha channel holds brightness with HDR, so drop it
+            if let Err(err) = image.to_rgb8().save(&path) {
+                error!("Failed to write frame {}: {err}", path.display());
+            }
+        }
+        CaptureSink::Encoder(encoder) => {
+            // A failed frame still takes its place, so later ones can follow
+            let bytes = image
+                .map(|image| image.to_rgba8().into_raw())
+                .unwrap_or_default();
+            run.pending.insert(frame.sequence, bytes);
+            let Some(stdin) = encoder.stdin.as_mut() else {
+                return;
+            };
+            let run = &mut *run;
+            while let Some(bytes) = run.pending.remove(&run.next_piped) {
+                if let Err(err) = stdin.write_all(&bytes) {
+                    error!(
+                        "Failed to pipe frame {} to the encoder: {err}",
+                        run.next_piped
+                    );
+                }
+                run.next_piped += 1;
+            }
+        }
+    }
+}
+
+fn stop_when_captured(
+    run: Res<CaptureRun>,
+    mut sink: ResMut<CaptureSink>,
+    step: Res<SimStep>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let Some(end_step) = run.end_step else {
+        return;
+    };
+    if step.0 <= end_step || run.in_flight > 0 {
+        return;
+    }
+
+    if let CaptureSink::Encoder(encoder) = sink.as_mut() {
+        // Closing stdin tells the encoder the stream is over
+        drop(encoder.stdin.take());
+        if let Err(err) = encoder.wait() {
+            error!("Encoder did not finish: {err}");
+        }
+    }
+    info!("Captured steps {} to {end_step}", run.start_step);
+    exit.send(AppExit::Success);
+}
@@ -0,0 +1,185 @@
+use std::path::PathBuf;
+
+use bevy::log::Level;
+use clap::{Parser, ValueEnum};
+
+/// A particle simulation where atoms form from protons and neutrons.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// Scenario file (RON) describing the initial conditions
+    #[arg(long)]
+    pub scenario: Option<PathBuf>,
+
+    /// Restore the particles of a snapshot instead of spawning the scenario
+    #[arg(long, conflicts_with = "scenario")]
+    pub snapshot: Option<PathBuf>,
+
+    /// Physics preset (RON) replacing the scenario's constants, as saved
+    /// from the parameter panel
+    #[arg(long)]
+    pub physics: Option<PathBuf>,
+
+    /// Seed for the simulation RNG, random if omitted
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Fixed simulation steps per second
+    #[arg(long, default_value_t = 100.0, value_parser = parse_positive)]
+    pub hz: f64,
+
+    /// Number of protons, replacing the scenario's proton groups
+    #[arg(long)]
+    pub protons: Option<usize>,
+
+    /// Number of neutrons, replacing the scenario's neutron groups
+    #[arg(long)]
+    pub neutrons: Option<usize>,
+
+    /// Number of electrons, replacing the scenario's electron groups
+    #[arg(long)]
+    pub electrons: Option<usize>,
+
+    /// Simulate without a window, writing stats and snapshots to `--output`
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Stop after this many fixed steps, counted from the snapshot's step
+    /// with `--snapshot`
+    #[arg(long)]
+    pub max_steps: Option<u64>,
+
+    /// Stop after this many seconds of wall-clock time (headless only)
+    #[arg(long, requires = "headless", value_parser = parse_positive)]
+    pub budget: Option<f64>,
+
+    /// Directory for stats and snapshots
+    #[arg(long, default_value = "output")]
+    pub output: PathBuf,
+
+    /// Write a snapshot every this many steps, 0 only writes the final one
+    #[arg(long, default_value_t = 0)]
+    pub snapshot_interval: u64,
+
+    /// Render every fixed step into numbered PNG frames in this directory
+    #[arg(long, conflicts_with_all = ["headless", "capture_pipe"])]
+    pub capture: Option<PathBuf>,
+
+    /// Pipe every fixed step as a raw RGBA frame into this encoder command,
+    /// e.g. "ffmpeg -f rawvideo -pix_fmt rgba -s 1920x1080 -r 60 -i - out.mp4"
+    #[arg(long, conflicts_with = "headless")]
+    pub capture_pipe: Option<String>,
+
+    /// First step to capture
+    #[arg(long, default_value_t = 0)]
+    pub capture_start: u64,
+
+    /// Last step to capture, exiting once it is written
+    #[arg(long)]
+    pub capture_end: Option<u64>,
+
+    /// Window and capture resolution, as WIDTHxHEIGHT
+    #[arg(long, value_parser = parse_resolution)]
+    pub resolution: Option<Resolution>,
+
+    #[arg(long, value_enum, default_value_t = LogLevel::Info)]
+    pub log_level: LogLevel,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+fn parse_resolution(value: &str) -> Result<Resolution, String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or(format!("Expected WIDTHxHEIGHT, got {value}"))?;
+    let parse = |side: &str| {
+        side.parse::<u32>()
+            .map_err(|err| format!("Invalid resolution {value}: {err}"))
+    };
+    Ok(Resolution {
+        width: parse(width)?,
+        height: parse(height)?,
+    })
+}
+
+/// A finite number above zero, for rates and durations
+fn parse_positive(value: &str) -> Result<f64, String> {
+    let number = value
+        .parse::<f64>()
+        .map_err(|err| format!("Invalid number {value}: {err}"))?;
+    if number.is_finite() && number > 0.0 {
+        Ok(number)
+    } else {
+        Err(format!("Expected a positive number, got {value}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => Self::ERROR,
+            LogLevel::Warn => Self::WARN,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Trace => Self::TRACE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, Parser};
+
+    use super::{parse_positive, Cli};
+
+    #[test]
+    fn accepts_positive_numbers() {
+        assert_eq!(parse_positive("100"), Ok(100.0));
+        assert_eq!(parse_positive("0.5"), Ok(0.5));
+        assert_eq!(parse_positive("1e3"), Ok(1000.0));
+    }
+
+    #[test]
+    fn rejects_zero_negative_and_non_finite_numbers() {
+        for value in ["0", "-0", "-1", "inf", "NaN"] {
+            assert_eq!(
+                parse_positive(value),
+                Err(format!("Expected a positive number, got {value}"))
+            );
+        }
+        assert_eq!(
+            parse_positive("fast"),
+            Err("Invalid number fast: invalid float literal".to_string())
+        );
+    }
+
+    #[test]
+    fn checks_rates_on_the_command_line() {
+        Cli::command().debug_assert();
+        assert_eq!(
+            Cli::try_parse_from(["universe", "--hz", "60"])
+                .map(|cli| cli.hz)
+                .ok(),
+            Some(60.0)
+        );
+        assert_eq!(
+            Cli::try_parse_from(["universe", "--hz", "0"])
+                .map(|cli| cli.hz)
+                .ok(),
+            None
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_dynamics::Velocity;
+use mechanics::{
+    snapshot::Snapshot, Kind, MechanicsPlugin, Particle, PhysicsConstants,
+    SimRng, SimStats, SimStep,
+};
+
+/// Runs the simulation without a window or renderer, as fast as possible,
+/// writing statistics and snapshots to `output_dir`. Expects a
+/// [`StatsFile`] opened in `output_dir`.
+pub struct HeadlessPlugin {
+    /// Stop once the simulation reaches this fixed step
+    pub end_step: Option<u64>,
+    /// Stop after this much wall-clock time
+    pub budget: Option<Duration>,
+    pub output_dir: PathBuf,
+    /// Write a snapshot every this many steps, `0` only writes the final one
+    pub snapshot_interval: u64,
+}
+
+/// The CSV file every fixed step's statistics are appended to
+#[derive(Resource)]
+pub struct StatsFile(BufWriter<File>);
+
+impl StatsFile {
+    /// Creates `output_dir` and a `stats.csv` in it with the header row
+    pub fn create(output_dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(output_dir).map_err(|err| {
+            format!(
+                "Failed to create output directory {}: {}",
+                output_dir.display(),
+                err
+            )
+        })?;
+        let path = output_dir.join("stats.csv");
+        let file = File::create(&path).map_err(|err| {
+            format!("Failed to create stats file {}: {}", path.display(), err)
+        })?;
+        let mut stats = BufWriter::new(file);
+        writeln!(
+            stats,
+            "step,time,protons,neutrons,electrons,photons,free_nucleons,atoms,elements"
+        )
+        .map_err(|err| format!("Failed to write stats header: {err}"))?;
+        Ok(Self(stats))
+    }
+}
+
+#[derive(Resource)]
+struct HeadlessRun {
+    end_step: Option<u64>,
+    budget: Option<Duration>,
+    output_dir: PathBuf,
+    snapshot_interval: u64,
+    started: Instant,
+}
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((MinimalPlugins, TransformPlugin, MechanicsPlugin));
+
+        // Every update advances virtual time by exactly one fixed step, so the
+        // simulation runs as fast as the CPU allows instead of in real time
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep))
+            .insert_resource(HeadlessRun {
+                end_step: self.end_step,
+                budget: self.budget,
+                output_dir: self.output_dir.clone(),
+                snapshot_interval: self.snapshot_interval,
+                started: Instant::now(),
+            })
+            .add_systems(
+                FixedPostUpdate,
+                (record_stats, stop_when_done).chain(),
+            );
+    }
+}
+
+fn record_stats(
+    run: Res<HeadlessRun>,
+    mut file: ResMut<StatsFile>,
+    step: Res<SimStep>,
+    stats: Res<SimStats>,
+    time: Res<Time>,
+    physics: Res<PhysicsConstants>,
+    rng: Res<SimRng>,
+    particles: Query<(&Particle, &Transform, &Velocity)>,
+) {
+    let elements = stats
+        .element_counts
+        .iter()
+        .map(|(element, count)| format!("{}:{count}", element.symbol()))
+        .collect::<Vec<_>>()
+        .join(";");
+    if let Err(err) = writeln!(
+        file.0,
+        "{},{:.4},{},{},{},{},{},{},{}",
+        step.0,
+        time.elapsed_secs(),
+        stats.particle_count(Kind::Proton),
+        stats.particle_count(Kind::Neutron),
+        stats.particle_count(Kind::Electron),
+        stats.particle_count(Kind::Photon),
+        stats.free_nucleons,
+        stats.atom_count,
+        elements,
+    ) {
+        error!("Failed to write stats: {err}");
+    }
+
+    if run.snapshot_interval > 0 && step.0 % run.snapshot_interval == 0 {
+        let snapshot =
+            Snapshot::capture(step.0, rng.seed(), *physics, particles.iter());
+        write_snapshot(&run, &snapshot);
+    }
+}
+
+fn stop_when_done(
+    run: Res<HeadlessRun>,
+    mut file: ResMut<StatsFile>,
+    step: Res<SimStep>,
+    physics: Res<PhysicsConstants>,
+    rng: Res<SimRng>,
+    particles: Query<(&Particle, &Transform, &Velocity)>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let out_of_steps = run.end_step.is_some_and(|end| step.0 >= end);
+    let out_of_time = run
+        .budget
+        .is_some_and(|budget| run.started.elapsed() >= budget);
+    if !out_of_steps && !out_of_time {
+        return;
+    }
+
+    let snapshot =
+        Snapshot::capture(step.0, rng.seed(), *physics, particles.iter());
+    write_snapshot(&run, &snapshot);
+    if let Err(err) = file.0.flush() {
+        error!("Failed to flush stats: {err}");
+    }
+
+    info!(
+        "Headless run finished after {} steps in {:.1?}",
+        step.0,
+        run.started.elapsed()
+    );
+    exit.send(AppExit::Success);
+}
+
+fn write_snapshot(run: &HeadlessRun, snapshot: &Snapshot) {
+    let path = run
+        .output_dir
+        .join(format!("snapshot_{:08}.ron", snapshot.step));
+    if let Err(err) = snapshot.save(path) {
+        error!("{err}");
+    }
+}
@@ -1,18 +1,140 @@
-use bevy::prelude::*;
+mod capture;
+mod cli;
+mod headless;
+
+use std::time::Duration;
+
+use bevy::{log::LogPlugin, prelude::*, window::WindowResolution};
+use capture::{CaptureOutput, CapturePlugin, CaptureSink};
+use clap::Parser;
+use cli::Cli;
+use headless::{HeadlessPlugin, StatsFile};
 use hud::HudPlugin;
-use mechanics::MechanicsPlugin;
+use mechanics::{
+    scenario::Scenario, snapshot::Snapshot, Kind, MechanicsPlugin,
+    PhysicsConstants, SimRng, SimStep,
+};
 use view::ViewPlugin;
 
 fn main() {
+    let cli = Cli::parse();
     let mut app = App::new();
 
-    app.insert_resource(Time::<Fixed>::from_hz(100.0));
+    app.insert_resource(Time::<Fixed>::from_hz(cli.hz));
+
+    let mut scenario = match &cli.scenario {
+        Some(path) => {
+            Scenario::load(path).unwrap_or_else(|err| exit_with(&err))
+        }
+        None => Scenario::default(),
+    };
+    for (kind, count) in [
+        (Kind::Proton, cli.protons),
+        (Kind::Neutron, cli.neutrons),
+        (Kind::Electron, cli.electrons),
+    ] {
+        if let Some(count) = count {
+            scenario.set_count(kind, count);
+        }
+    }
+    if let Some(path) = &cli.physics {
+        scenario.physics =
+            PhysicsConstants::load(path).unwrap_or_else(|err| exit_with(&err));
+    }
+    app.insert_resource(scenario);
+
+    // Steps count on from a restored snapshot, `--max-steps` from there
+    let mut start_step = 0;
+    if let Some(path) = &cli.snapshot {
+        let snapshot =
+            Snapshot::load(path).unwrap_or_else(|err| exit_with(&err));
+        start_step = snapshot.step;
+        app.insert_resource(snapshot);
+    }
+    let end_step = cli.max_steps.map(|max| start_step.saturating_add(max));
+
+    if let Some(seed) = cli.seed {
+        app.insert_resource(SimRng::new(seed));
+    }
+
+    let log = LogPlugin {
+        level: cli.log_level.into(),
+        ..default()
+    };
+
+    if cli.headless {
+        let budget = cli.budget.map(|secs| {
+            Duration::try_from_secs_f64(secs).unwrap_or_else(|err| {
+                exit_with(&format!("Invalid budget {secs}: {err}"))
+            })
+        });
+        let stats = StatsFile::create(&cli.output)
+            .unwrap_or_else(|err| exit_with(&err));
+        app.insert_resource(stats).add_plugins((
+            log,
+            HeadlessPlugin {
+                end_step,
+                budget,
+                output_dir: cli.output,
+                snapshot_interval: cli.snapshot_interval,
+            },
+        ));
+        app.run();
+        return;
+    }
+
+    let mut window = Window::default();
+    if let Some(resolution) = cli.resolution {
+        // Frames should come out at exactly this size, whatever the display
+        window.resolution = WindowResolution::new(
+            resolution.width as f32,
+            resolution.height as f32,
+        )
+        .with_scale_factor_override(1.0);
+        window.resizable = false;
+    }
 
     // Bevy plugins
-    app.add_plugins((DefaultPlugins, MeshPickingPlugin));
+    app.add_plugins((
+        DefaultPlugins.set(log).set(WindowPlugin {
+            primary_window: Some(window),
+            ..default()
+        }),
+        MeshPickingPlugin,
+    ));
 
     // Internal plugins
     app.add_plugins((MechanicsPlugin, ViewPlugin, HudPlugin));
 
+    let capture = match (cli.capture, cli.capture_pipe) {
+        (Some(dir), _) => Some(CaptureOutput::Frames(dir)),
+        (None, Some(command)) => Some(CaptureOutput::Pipe(command)),
+        (None, None) => None,
+    };
+    if let Some(output) = capture {
+        let sink =
+            CaptureSink::open(&output).unwrap_or_else(|err| exit_with(&err));
+        app.insert_resource(sink).add_plugins(CapturePlugin {
+            start_step: cli.capture_start,
+            end_step: cli.capture_end,
+        });
+    }
+
+    if let Some(end_step) = end_step {
+        app.add_systems(
+            FixedPostUpdate,
+            move |step: Res<SimStep>, mut exit: EventWriter<AppExit>| {
+                if step.0 >= end_step {
+                    exit.send(AppExit::Success);
+                }
+            },
+        );
+    }
+
     app.run();
 }
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1);
+}
@@ -0,0 +1,95 @@
+//! Headless benchmark of how well the particle view batches.
+//!
+//! No GPU is needed: the benchmark runs `init_particles` on a plain `App`,
+//! counts the meshes and materials it created, and then times a walk over
+//! the mesh, material and transform of every particle, grouping them into
+//! batches by (mesh, material). That walk stands in for the per-frame work
+//! the renderer does on those components; it is not the renderer's own
+//! extraction, which needs a render device to exist at all.
+//!
+//! Run with `cargo bench -p view`.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use bevy::asset::AssetPlugin;
+use bevy::prelude::*;
+use mechanics::particle::{Kind, Particle};
+use view::{init_particles, ParticleAssets};
+
+const PARTICLE_COUNTS: [usize; 3] = [1_000, 10_000, 100_000];
+const FRAMES: u32 = 20;
+
+fn main() {
+    println!(
+        "{:>10} {:>10} {:>10} {:>8} {:>12} {:>14}",
+        "particles", "meshes", "materials", "batches", "init", "walk/frame"
+    );
+    for count in PARTICLE_COUNTS {
+        bench(count);
+    }
+}
+
+fn bench(count: usize) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), TransformPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .init_resource::<ParticleAssets>()
+        .add_systems(Update, init_particles);
+
+    // Deterministic layout, the positions only matter for the transforms
+    let side = (count as f32).cbrt().ceil() as usize;
+    let kinds = [Kind::Proton, Kind::Neutron];
+    app.world_mut().spawn_batch((0..count).map(move |i| {
+        let position = Vec3::new(
+            (i % side) as f32,
+            (i / side % side) as f32,
+            (i / (side * side)) as f32,
+        ) * 3.0;
+        (
+            Particle::from_kind(kinds[i % kinds.len()]),
+            Transform::from_translation(position),
+        )
+    }));
+
+    let started = Instant::now();
+    app.update();
+    let init = started.elapsed();
+
+    let world = app.world_mut();
+    let meshes = world.resource::<Assets<Mesh>>().len();
+    let materials = world.resource::<Assets<StandardMaterial>>().len();
+
+    let mut query = world.query::<(
+        &Mesh3d,
+        &MeshMaterial3d<StandardMaterial>,
+        &GlobalTransform,
+    )>();
+    let mut instances = Vec::with_capacity(count);
+    let mut batches = HashMap::new();
+    let mut walk = Duration::ZERO;
+    for _ in 0..FRAMES {
+        instances.clear();
+        batches.clear();
+
+        let started = Instant::now();
+        for (mesh, material, transform) in query.iter(world) {
+            instances.push((mesh.id(), material.id(), transform.affine()));
+        }
+        for (mesh, material, _) in &instances {
+            *batches.entry((*mesh, *material)).or_insert(0_usize) += 1;
+        }
+        walk += started.elapsed();
+    }
+
+    println!(
+        "{:>10} {:>10} {:>10} {:>8} {:>12.2?} {:>14.2?}",
+        count,
+        meshes,
+        materials,
+        batches.len(),
+        init,
+        walk / FRAMES
+    );
+}
@@ -1,4 +1,74 @@
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
+use mechanics::particle::Kind;
 
 #[derive(Component, Debug, Clone, Copy)]
 pub struct ParticleView;
+
+/// The orbit camera looking at the simulation
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MainCamera;
+
+/// The colour a particle is currently drawn with under the
+/// [`ColorScheme`](crate::ColorScheme)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ParticleColor(pub Color);
+
+/// Colour a particle is drawn with whatever the colour scheme, set by
+/// recolouring a selection
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ColorOverride(pub Color);
+
+/// The representation a particle is currently drawn with, picked from its
+/// distance to the camera by the [`LodPolicy`](crate::LodPolicy)
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParticleLod {
+    #[default]
+    Full,
+    Low,
+    /// A flat disc that is turned towards the camera every frame
+    Impostor,
+}
+
+/// The translucent, pickable sphere drawn around an [`Atom`](mechanics::Atom)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AtomHitbox;
+
+/// Shows an atom's hitbox as if hovered, for pointing at it from the HUD
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Highlighted;
+
+/// Ring buffer of a particle's most recent positions, oldest first, drawn
+/// as a fading line behind it.
+#[derive(Component, Debug, Default, Clone)]
+pub struct Trail {
+    pub positions: VecDeque<Vec3>,
+}
+
+/// The plane the [`FieldView`](crate::FieldView) heatmap is drawn on
+#[derive(Component, Debug, Clone)]
+pub struct FieldSlice {
+    pub image: Handle<Image>,
+}
+
+/// The electron cloud drawn around an atom, for the electron count it was
+/// built for
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ElectronCloud {
+    pub electron_count: u32,
+    /// Child entity holding the point cloud mesh
+    pub points: Entity,
+}
+
+/// Camera rendering the [`NucleusInspector`](crate::NucleusInspector) image
+#[derive(Component, Debug, Clone, Copy)]
+pub struct InspectorCamera;
+
+/// Enlarged stand-in for a nucleon of the inspected atom, only visible to
+/// the [`InspectorCamera`]
+#[derive(Component, Debug, Clone, Copy)]
+pub struct NucleusProxy {
+    pub source: Entity,
+    pub kind: Kind,
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+/// Requests for the orbit camera, sent by the keyboard shortcuts in
+/// `camera_shortcuts` or from other crates such as the HUD
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraCommand {
+    /// Move the orbit pivot to the selected atom and zoom to fit it
+    FocusSelected,
+    /// Move the orbit pivot to this atom and zoom to fit it
+    FocusAtom(Entity),
+    /// Start or stop keeping the selected atom at the orbit pivot
+    ToggleFollow,
+    /// Move and zoom out until every particle is in view
+    FrameAll,
+    SaveBookmark(usize),
+    RestoreBookmark(usize),
+}
+
+/// Tool actions that aren't a click in the scene, sent by the keyboard
+/// shortcuts in `tool_shortcuts` or from the HUD toolbar
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolCommand {
+    /// Revert the most recent action on the [`UndoStack`](crate::UndoStack)
+    Undo,
+    /// Remove every particle in the [`Selection`](crate::Selection)
+    DeleteSelection,
+}
+
+/// Operations on the whole [`Selection`](crate::Selection), sent from the
+/// HUD selection panel
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum SelectionCommand {
+    /// Freeze every particle, or thaw them all if they already are
+    ToggleFrozen,
+    /// Draw the particles in this colour whatever the colour scheme, or
+    /// return them to it
+    Recolour(Option<Color>),
+    /// Write the particles to `prefabs/<name>.ron`
+    SavePrefab(String),
+    Clear,
+}
@@ -1,7 +1,11 @@
 mod components;
+mod events;
 mod plugins;
+mod resources;
 mod systems;
 
 pub use components::*;
+pub use events::*;
 pub use plugins::*;
+pub use resources::*;
 pub use systems::*;
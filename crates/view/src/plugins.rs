@@ -1,7 +1,22 @@
 use bevy::prelude::*;
 use bevy_blendy_cameras::BlendyCamerasPlugin;
 
-use crate::{init_particles, setup_view, spawn_particles, toggle_debug};
+use crate::{
+    apply_color_scheme, brush_tool, camera_shortcuts, control_field_view,
+    cycle_color_scheme, delete_tool, drag_tool, draw_field_lines,
+    draw_nucleus_bonds, draw_selection, draw_trails, fire_tool, follow_atom,
+    handle_camera_commands, handle_selection_commands, handle_tool_commands,
+    init_particles, keyboard_free, maintain_atom_hitbox_colour, record_trails,
+    select_tool, setup_field_slice, setup_nucleus_inspector, setup_view,
+    spawn_atom_hitbox, spawn_tool, sync_electron_clouds,
+    sync_nucleus_inspector, sync_selection, sync_trails, take_screenshot,
+    toggle_debug, toggle_nucleus_inspector, toggle_orbitals, toggle_trails,
+    tool_shortcuts, update_field_slice, update_particle_lod, CameraCommand,
+    CameraRig, ColorScheme, FieldView, InspectorGizmos, LodPolicy, Marquee,
+    NucleusInspector, OrbitalClouds, OrbitalView, ParticleAssets,
+    ParticleInspector, ScreenshotSettings, Selection, SelectionCommand,
+    TextInputFocus, ToolCommand, Tools, TrailSettings, UndoStack,
+};
 
 pub struct ViewPlugin;
 
@@ -13,7 +28,85 @@ impl Plugin for ViewPlugin {
         })
         .insert_resource(ClearColor(Color::srgb_u8(30, 30, 46)))
         .add_plugins(BlendyCamerasPlugin)
-        .add_systems(Startup, (setup_view, spawn_particles))
-        .add_systems(Update, (init_particles, toggle_debug));
+        .init_resource::<ParticleAssets>()
+        .init_resource::<LodPolicy>()
+        .init_resource::<TrailSettings>()
+        .init_resource::<FieldView>()
+        .init_resource::<ColorScheme>()
+        .init_resource::<CameraRig>()
+        .init_resource::<OrbitalView>()
+        .init_resource::<OrbitalClouds>()
+        .init_resource::<ScreenshotSettings>()
+        .init_resource::<NucleusInspector>()
+        .init_resource::<TextInputFocus>()
+        .init_resource::<ParticleInspector>()
+        .init_resource::<Tools>()
+        .init_resource::<UndoStack>()
+        .init_resource::<Selection>()
+        .init_resource::<Marquee>()
+        .init_gizmo_group::<InspectorGizmos>()
+        .add_event::<CameraCommand>()
+        .add_event::<ToolCommand>()
+        .add_event::<SelectionCommand>()
+        .add_systems(
+            Startup,
+            (setup_view, setup_field_slice, setup_nucleus_inspector),
+        )
+        .add_systems(
+            Update,
+            (
+                init_particles,
+                update_particle_lod.after(init_particles),
+                (cycle_color_scheme.run_if(keyboard_free), apply_color_scheme)
+                    .chain()
+                    .after(update_particle_lod),
+                toggle_debug.run_if(keyboard_free),
+                (
+                    toggle_trails.run_if(keyboard_free),
+                    sync_trails,
+                    draw_trails,
+                )
+                    .chain(),
+                (
+                    control_field_view.run_if(keyboard_free),
+                    update_field_slice,
+                    draw_field_lines,
+                )
+                    .chain(),
+                (
+                    camera_shortcuts.run_if(keyboard_free),
+                    handle_camera_commands,
+                    follow_atom,
+                )
+                    .chain(),
+                spawn_atom_hitbox,
+                (toggle_orbitals.run_if(keyboard_free), sync_electron_clouds)
+                    .chain(),
+                maintain_atom_hitbox_colour.after(sync_electron_clouds),
+                take_screenshot.run_if(keyboard_free),
+                (
+                    toggle_nucleus_inspector.run_if(keyboard_free),
+                    sync_nucleus_inspector,
+                    draw_nucleus_bonds,
+                )
+                    .chain(),
+                (
+                    sync_selection,
+                    tool_shortcuts.run_if(keyboard_free),
+                    (
+                        select_tool,
+                        spawn_tool,
+                        brush_tool,
+                        drag_tool,
+                        delete_tool,
+                        fire_tool,
+                    ),
+                    (handle_tool_commands, handle_selection_commands),
+                    draw_selection,
+                )
+                    .chain(),
+            ),
+        )
+        .add_systems(FixedPostUpdate, record_trails);
     }
 }
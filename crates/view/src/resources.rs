@@ -0,0 +1,712 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            Extent3d, TextureDimension, TextureFormat, TextureUsages,
+        },
+    },
+};
+use mechanics::particle::Kind;
+use mechanics::snapshot::ParticleState;
+use mechanics::FieldKind;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::ParticleLod;
+
+/// Mesh and material handles shared between particles.
+///
+/// Sharing the handles lets the renderer batch all particles of the same
+/// colour into a single instanced draw, instead of preparing one mesh and
+/// one material per particle. Materials are created on demand per colour,
+/// so colour schemes quantize their colours to keep the cache small.
+#[derive(Resource, Debug)]
+pub struct ParticleAssets {
+    /// Unit spheres and a unit disc for each [`ParticleLod`], scaled to the
+    /// particle radius through its `Transform`
+    pub full_mesh: Handle<Mesh>,
+    pub low_mesh: Handle<Mesh>,
+    pub impostor_mesh: Handle<Mesh>,
+    /// Keyed by colour and whether the material is unlit, which impostors
+    /// are so the flat discs don't shade like a plane
+    materials: HashMap<([u8; 4], bool), Handle<StandardMaterial>>,
+}
+
+impl ParticleAssets {
+    pub fn mesh(&self, lod: ParticleLod) -> Handle<Mesh> {
+        match lod {
+            ParticleLod::Full => self.full_mesh.clone(),
+            ParticleLod::Low => self.low_mesh.clone(),
+            ParticleLod::Impostor => self.impostor_mesh.clone(),
+        }
+    }
+
+    pub fn material(
+        &mut self,
+        color: Color,
+        lod: ParticleLod,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        let unlit = lod == ParticleLod::Impostor;
+        let key = (color.to_srgba().to_u8_array(), unlit);
+        self.materials
+            .entry(key)
+            .or_insert_with(|| {
+                materials.add(StandardMaterial {
+                    base_color: color,
+                    unlit,
+                    ..default()
+                })
+            })
+            .clone()
+    }
+}
+
+impl FromWorld for ParticleAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let full_mesh = meshes
+            .add(Sphere::new(1.0).mesh().ico(10).expect("valid subdivisions"));
+        let low_mesh = meshes
+            .add(Sphere::new(1.0).mesh().ico(1).expect("valid subdivisions"));
+        let impostor_mesh = meshes.add(Circle::new(1.0));
+
+        Self {
+            full_mesh,
+            low_mesh,
+            impostor_mesh,
+            materials: HashMap::new(),
+        }
+    }
+}
+
+/// Camera distances at which particles switch to cheaper representations.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct LodPolicy {
+    pub enabled: bool,
+    /// Closer than this, particles are full ico spheres
+    pub full_distance: f32,
+    /// Closer than this, particles are low-poly spheres, beyond it they are
+    /// camera-facing impostor discs
+    pub low_distance: f32,
+}
+
+impl Default for LodPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            full_distance: 150.0,
+            low_distance: 400.0,
+        }
+    }
+}
+
+impl LodPolicy {
+    pub fn level(&self, distance: f32) -> ParticleLod {
+        if !self.enabled || distance < self.full_distance {
+            ParticleLod::Full
+        } else if distance < self.low_distance {
+            ParticleLod::Low
+        } else {
+            ParticleLod::Impostor
+        }
+    }
+}
+
+/// Which particles get a [`Trail`](crate::Trail), and how long it is.
+#[derive(Resource, Debug, Clone)]
+pub struct TrailSettings {
+    pub enabled: bool,
+    pub kinds: HashSet<Kind>,
+    /// Number of fixed steps a trail remembers
+    pub length: usize,
+}
+
+impl Default for TrailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            kinds: HashSet::from([Kind::Proton, Kind::Neutron]),
+            length: 200,
+        }
+    }
+}
+
+impl TrailSettings {
+    pub fn shows(&self, kind: Kind) -> bool {
+        self.enabled && self.kinds.contains(&kind)
+    }
+
+    pub fn toggle_kind(&mut self, kind: Kind) {
+        if !self.kinds.remove(&kind) {
+            self.kinds.insert(kind);
+        }
+    }
+}
+
+/// Heatmap of a potential sampled on a movable slice plane, and optional
+/// electric field lines.
+#[derive(Resource, Debug, Clone)]
+pub struct FieldView {
+    /// The potential to sample, `None` hides the slice
+    pub field: Option<FieldKind>,
+    pub normal: Dir3,
+    /// Distance of the slice from the origin along `normal`
+    pub offset: f32,
+    /// Side length of the square slice
+    pub extent: f32,
+    /// Samples along each side of the slice
+    pub resolution: u32,
+    pub field_lines: bool,
+}
+
+impl Default for FieldView {
+    fn default() -> Self {
+        Self {
+            field: None,
+            normal: Dir3::Y,
+            offset: 0.0,
+            extent: 400.0,
+            resolution: 128,
+            field_lines: false,
+        }
+    }
+}
+
+impl FieldView {
+    pub fn slice_transform(&self) -> Transform {
+        Transform::from_translation(self.normal * self.offset)
+            .with_rotation(Quat::from_rotation_arc(Vec3::Y, *self.normal))
+            .with_scale(Vec3::splat(self.extent))
+    }
+}
+
+/// How particles are coloured, and the legend explaining it.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ColorScheme {
+    pub mode: ColorMode,
+    /// Labelled colours for the current mode, kept up to date by
+    /// `apply_color_scheme` for the HUD legend
+    pub legend: Vec<(String, Color)>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub enum ColorMode {
+    #[default]
+    Kind,
+    Speed,
+    KineticEnergy,
+    NetForce,
+    Element,
+    Cluster,
+}
+
+impl ColorMode {
+    pub fn next(self) -> Self {
+        Self::iter()
+            .cycle()
+            .skip_while(|mode| *mode != self)
+            .nth(1)
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Kind => "Kind",
+            Self::Speed => "Speed",
+            Self::KineticEnergy => "Kinetic energy",
+            Self::NetForce => "Net force",
+            Self::Element => "Element",
+            Self::Cluster => "Cluster",
+        }
+        .fmt(f)
+    }
+}
+
+/// Camera state that outlives a single [`CameraCommand`](crate::CameraCommand)
+#[derive(Resource, Debug, Default)]
+pub struct CameraRig {
+    /// Atom kept at the orbit pivot every frame
+    pub follow: Option<Entity>,
+    pub bookmarks: HashMap<usize, CameraPose>,
+}
+
+/// Where the orbit camera looks from, as the targets of its controller
+#[derive(Debug, Clone, Copy)]
+pub struct CameraPose {
+    pub focus: Vec3,
+    pub radius: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Whether and how electron clouds are drawn around atoms.
+#[derive(Resource, Debug, Clone)]
+pub struct OrbitalView {
+    pub enabled: bool,
+    /// Draw the clouds of the neutral atom, with as many electrons as
+    /// protons, instead of the electrons actually bound to it. On by
+    /// default, as `detect_atoms` binds no electrons yet.
+    pub assume_neutral: bool,
+    pub points_per_electron: usize,
+}
+
+impl Default for OrbitalView {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            assume_neutral: true,
+            points_per_electron: 300,
+        }
+    }
+}
+
+/// Point cloud meshes per electron count, shared between atoms with the
+/// same configuration, and the material they are drawn with
+#[derive(Resource, Debug)]
+pub struct OrbitalClouds {
+    pub meshes: HashMap<u32, Handle<Mesh>>,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for OrbitalClouds {
+    fn from_world(world: &mut World) -> Self {
+        let material = world.resource_mut::<Assets<StandardMaterial>>().add(
+            StandardMaterial {
+                // Tinted per orbital through vertex colours
+                base_color: Color::WHITE,
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            },
+        );
+
+        Self {
+            meshes: HashMap::new(),
+            material,
+        }
+    }
+}
+
+/// Where the screenshot key saves frames and their metadata sidecars.
+#[derive(Resource, Debug, Clone)]
+pub struct ScreenshotSettings {
+    pub directory: PathBuf,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("screenshots"),
+        }
+    }
+}
+
+/// Particles under inspection, set by hovering and clicking particles.
+#[derive(Resource, Debug, Default)]
+pub struct ParticleInspector {
+    pub hovered: Option<Entity>,
+    /// Shown in the inspector panel until unpinned
+    pub pinned: Option<Entity>,
+}
+
+/// Everything selected, mirrored from the [`Selected`](mechanics::Selected) markers every frame
+/// by `sync_selection`. The markers stay the source of truth, since the
+/// simulation carries them over when it rebuilds an atom.
+#[derive(Resource, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    pub atoms: HashSet<Entity>,
+    /// Particles selected on their own
+    pub particles: HashSet<Entity>,
+    /// Every particle in the selection, loose or bound in a selected atom
+    pub members: HashSet<Entity>,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.atoms.is_empty() && self.particles.is_empty()
+    }
+}
+
+/// Screen-space outline dragged by the select tool, a box between the first
+/// and last point or a lasso through all of them.
+#[derive(Resource, Debug, Default)]
+pub struct Marquee {
+    pub points: Vec<Vec2>,
+    pub lasso: bool,
+}
+
+impl Marquee {
+    /// Cursor travel in pixels before a press counts as a drag rather than
+    /// a click
+    pub const THRESHOLD: f32 = 4.0;
+
+    pub fn is_dragging(&self) -> bool {
+        self.points.first().is_some_and(|start| {
+            self.points
+                .iter()
+                .any(|point| point.distance(*start) > Self::THRESHOLD)
+        })
+    }
+
+    /// Corners of the box or points of the lasso, in drawing order
+    pub fn outline(&self) -> Vec<Vec2> {
+        match (self.lasso, self.points.first(), self.points.last()) {
+            (false, Some(start), Some(end)) => vec![
+                *start,
+                Vec2::new(end.x, start.y),
+                *end,
+                Vec2::new(start.x, end.y),
+            ],
+            _ => self.points.clone(),
+        }
+    }
+
+    /// Whether `point` is inside the outline, by the even-odd rule
+    pub fn contains(&self, point: Vec2) -> bool {
+        let outline = self.outline();
+        let mut inside = false;
+        for (i, a) in outline.iter().enumerate() {
+            let b = outline[(i + 1) % outline.len()];
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+/// Text field currently taking keyboard input. Keyboard shortcuts are
+/// suspended while one is set, so typing doesn't toggle views.
+#[derive(Resource, Debug, Default)]
+pub struct TextInputFocus(pub Option<Entity>);
+
+/// Exploded view of the nucleus of the selected atom, rendered off screen
+/// into `image` for the HUD to show.
+#[derive(Resource, Debug)]
+pub struct NucleusInspector {
+    pub enabled: bool,
+    pub image: Handle<Image>,
+    /// Atom the proxies currently stand in for
+    pub atom: Option<Entity>,
+}
+
+impl NucleusInspector {
+    /// Width and height of `image` in pixels
+    pub const RESOLUTION: u32 = 512;
+}
+
+impl FromWorld for NucleusInspector {
+    fn from_world(world: &mut World) -> Self {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: Self::RESOLUTION,
+                height: Self::RESOLUTION,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Bgra8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::RENDER_ATTACHMENT;
+
+        Self {
+            enabled: true,
+            image: world.resource_mut::<Assets<Image>>().add(image),
+            atom: None,
+        }
+    }
+}
+
+/// What a left click in the scene does, chosen from the HUD toolbar or with
+/// the number keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub enum ToolMode {
+    /// Clicking atoms selects them and clicking particles pins them
+    #[default]
+    Select,
+    /// Places one particle on the work plane
+    Spawn,
+    /// Sprays a cloud of particles on the work plane while held
+    Brush,
+    /// Pulls a particle or atom towards the cursor with a spring
+    Drag,
+    /// Removes the particle or atom under the cursor
+    Delete,
+    /// Shoots a particle from the camera along the view ray
+    Fire,
+}
+
+impl std::fmt::Display for ToolMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Select => "Select",
+            Self::Spawn => "Spawn",
+            Self::Brush => "Brush",
+            Self::Drag => "Drag",
+            Self::Delete => "Delete",
+            Self::Fire => "Fire",
+        }
+        .fmt(f)
+    }
+}
+
+/// Settings of the interactive tools. Particles are placed on the work
+/// plane, the horizontal plane through the orbit pivot.
+#[derive(Resource, Debug, Clone)]
+pub struct Tools {
+    pub mode: ToolMode,
+    /// Kind placed by the spawn, brush and fire tools
+    pub kind: Kind,
+    pub brush_radius: f32,
+    /// Particles per second sprayed by the brush
+    pub brush_rate: f32,
+    pub fire_speed: f32,
+    /// Spring constant of the drag tool, per unit mass so every kind
+    /// follows the cursor equally fast
+    pub drag_stiffness: f32,
+}
+
+impl Default for Tools {
+    fn default() -> Self {
+        Self {
+            mode: ToolMode::Select,
+            kind: Kind::Proton,
+            brush_radius: 20.0,
+            brush_rate: 60.0,
+            fire_speed: 100.0,
+            drag_stiffness: 50.0,
+        }
+    }
+}
+
+impl Tools {
+    /// Moves on to spawning the next kind of particle
+    pub fn cycle_kind(&mut self) {
+        self.kind = Kind::iter()
+            .cycle()
+            .skip_while(|kind| *kind != self.kind)
+            .nth(1)
+            .unwrap_or(Kind::Proton);
+    }
+}
+
+/// One undoable tool action.
+#[derive(Debug, Clone)]
+pub enum ToolAction {
+    /// Particles placed by the spawn, brush or fire tools
+    Spawned(Vec<Entity>),
+    /// Particles removed, with the entities they had, restored as new
+    /// entities
+    Deleted(Vec<(Entity, ParticleState)>),
+    /// Particles moved by the drag tool and where they started from
+    Dragged(Vec<(Entity, Vec3)>),
+}
+
+/// The last tool actions, most recent at the back.
+#[derive(Resource, Debug, Clone)]
+pub struct UndoStack {
+    pub actions: VecDeque<ToolAction>,
+    pub capacity: usize,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self {
+            actions: VecDeque::new(),
+            capacity: 50,
+        }
+    }
+}
+
+impl UndoStack {
+    /// Records `action`, forgetting the oldest once over capacity
+    pub fn push(&mut self, action: ToolAction) {
+        self.actions.push_back(action);
+        while self.actions.len() > self.capacity {
+            self.actions.pop_front();
+        }
+    }
+
+    /// Points the remaining actions at the entities `restored` in place of
+    /// removed ones, so undoing further back still finds them
+    pub fn remap(&mut self, restored: &HashMap<Entity, Entity>) {
+        let remap = |entity: &mut Entity| {
+            if let Some(new) = restored.get(entity) {
+                *entity = *new;
+            }
+        };
+        for action in &mut self.actions {
+            match action {
+                ToolAction::Spawned(entities) => {
+                    entities.iter_mut().for_each(remap);
+                }
+                ToolAction::Deleted(particles) => {
+                    particles.iter_mut().for_each(|(entity, _)| remap(entity));
+                }
+                ToolAction::Dragged(start) => {
+                    start.iter_mut().for_each(|(entity, _)| remap(entity));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bevy::prelude::*;
+    use mechanics::particle::Kind;
+    use mechanics::snapshot::ParticleState;
+
+    use super::{LodPolicy, Marquee, ToolAction, UndoStack};
+    use crate::ParticleLod;
+
+    #[test]
+    fn lod_switches_at_the_policy_distances() {
+        let policy = LodPolicy::default();
+
+        assert_eq!(policy.level(0.0), ParticleLod::Full);
+        assert_eq!(policy.level(149.0), ParticleLod::Full);
+        assert_eq!(policy.level(150.0), ParticleLod::Low);
+        assert_eq!(policy.level(399.0), ParticleLod::Low);
+        assert_eq!(policy.level(400.0), ParticleLod::Impostor);
+        assert_eq!(policy.level(1.0e6), ParticleLod::Impostor);
+    }
+
+    #[test]
+    fn disabled_lod_keeps_full_spheres() {
+        let policy = LodPolicy {
+            enabled: false,
+            ..LodPolicy::default()
+        };
+
+        assert_eq!(policy.level(1.0e6), ParticleLod::Full);
+    }
+
+    fn state(kind: Kind) -> ParticleState {
+        ParticleState {
+            kind,
+            position: [0.0; 3],
+            velocity: [0.0; 3],
+        }
+    }
+
+    #[test]
+    fn remap_follows_restored_entities_through_every_action() {
+        let [a, b, c, restored_a, restored_b] =
+            [1, 2, 3, 4, 5].map(Entity::from_raw);
+        let mut undo = UndoStack::default();
+        undo.push(ToolAction::Spawned(vec![a, c]));
+        undo.push(ToolAction::Dragged(vec![(b, Vec3::X), (c, Vec3::Y)]));
+        undo.push(ToolAction::Deleted(vec![(a, state(Kind::Proton))]));
+
+        undo.remap(&HashMap::from([(a, restored_a), (b, restored_b)]));
+
+        let Some(ToolAction::Spawned(spawned)) = undo.actions.front() else {
+            panic!("expected the spawn first");
+        };
+        assert_eq!(spawned, &[restored_a, c]);
+        let Some(ToolAction::Dragged(dragged)) = undo.actions.get(1) else {
+            panic!("expected the drag second");
+        };
+        assert_eq!(dragged, &[(restored_b, Vec3::X), (c, Vec3::Y)]);
+        let Some(ToolAction::Deleted(deleted)) = undo.actions.back() else {
+            panic!("expected the deletion last");
+        };
+        assert_eq!(deleted[0].0, restored_a);
+        assert_eq!(deleted[0].1.kind, Kind::Proton);
+    }
+
+    #[test]
+    fn push_forgets_the_oldest_action_over_capacity() {
+        let mut undo = UndoStack {
+            capacity: 2,
+            ..UndoStack::default()
+        };
+        for index in 1..=3 {
+            undo.push(ToolAction::Spawned(vec![Entity::from_raw(index)]));
+        }
+
+        let spawned: Vec<Entity> = undo
+            .actions
+            .iter()
+            .filter_map(|action| match action {
+                ToolAction::Spawned(entities) => entities.first().copied(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(spawned, [Entity::from_raw(2), Entity::from_raw(3)]);
+    }
+
+    #[test]
+    fn box_marquee_contains_points_between_its_corners() {
+        for (start, end) in [
+            (Vec2::ZERO, Vec2::new(100.0, 50.0)),
+            // Dragged up and to the left
+            (Vec2::new(100.0, 50.0), Vec2::ZERO),
+        ] {
+            let marquee = Marquee {
+                points: vec![start, end],
+                lasso: false,
+            };
+            assert!(marquee.contains(Vec2::new(50.0, 25.0)));
+            assert!(marquee.contains(Vec2::new(99.0, 1.0)));
+            assert!(!marquee.contains(Vec2::new(150.0, 25.0)));
+            assert!(!marquee.contains(Vec2::new(50.0, -1.0)));
+        }
+    }
+
+    #[test]
+    fn lasso_marquee_follows_its_outline() {
+        // A U shape open at the top, whose notch is outside
+        let marquee = Marquee {
+            points: vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(90.0, 0.0),
+                Vec2::new(90.0, 90.0),
+                Vec2::new(60.0, 90.0),
+                Vec2::new(60.0, 30.0),
+                Vec2::new(30.0, 30.0),
+                Vec2::new(30.0, 90.0),
+                Vec2::new(0.0, 90.0),
+            ],
+            lasso: true,
+        };
+        assert!(marquee.contains(Vec2::new(15.0, 60.0)));
+        assert!(marquee.contains(Vec2::new(75.0, 60.0)));
+        assert!(marquee.contains(Vec2::new(45.0, 15.0)));
+        assert!(!marquee.contains(Vec2::new(45.0, 60.0)));
+        assert!(!marquee.contains(Vec2::new(120.0, 15.0)));
+    }
+
+    #[test]
+    fn marquee_without_points_contains_nothing() {
+        assert!(!Marquee::default().contains(Vec2::ZERO));
+    }
+
+    #[test]
+    fn marquee_drags_past_the_threshold() {
+        let mut marquee = Marquee {
+            points: vec![Vec2::ZERO, Vec2::splat(1.0)],
+            lasso: true,
+        };
+        assert!(!marquee.is_dragging());
+        marquee
+            .points
+            .push(Vec2::new(Marquee::THRESHOLD + 1.0, 0.0));
+        assert!(marquee.is_dragging());
+    }
+}
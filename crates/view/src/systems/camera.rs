@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use bevy_blendy_cameras::OrbitCameraController;
+use mechanics::{particle::Particle, Atom, Selected};
+
+use crate::{CameraCommand, CameraPose, CameraRig};
+
+/// Orbit radius per unit of atom radius when focusing an atom
+const FOCUS_RADIUS_FACTOR: f32 = 8.0;
+/// Extra room around the particles when framing all of them
+const FRAME_MARGIN: f32 = 1.2;
+const BOOKMARK_KEYS: [KeyCode; 4] =
+    [KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4];
+
+// system that focuses the selected atom on pressing ., toggles following it
+// on pressing Shift + ., frames all particles on pressing H, and restores
+// camera bookmarks on pressing F1-F4 or saves them with Ctrl + F1-F4
+pub fn camera_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut commands: EventWriter<CameraCommand>,
+) {
+    let shift = keyboard_input.pressed(KeyCode::ShiftLeft)
+        || keyboard_input.pressed(KeyCode::ShiftRight);
+    let ctrl = keyboard_input.pressed(KeyCode::ControlLeft)
+        || keyboard_input.pressed(KeyCode::ControlRight);
+
+    if keyboard_input.just_pressed(KeyCode::Period) {
+        commands.send(if shift {
+            CameraCommand::ToggleFollow
+        } else {
+            CameraCommand::FocusSelected
+        });
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        commands.send(CameraCommand::FrameAll);
+    }
+
+    for (slot, key) in BOOKMARK_KEYS.into_iter().enumerate() {
+        if keyboard_input.just_pressed(key) {
+            commands.send(if ctrl {
+                CameraCommand::SaveBookmark(slot)
+            } else {
+                CameraCommand::RestoreBookmark(slot)
+            });
+        }
+    }
+}
+
+/// Applies [`CameraCommand`]s by moving the targets of the orbit controller,
+/// which then eases the camera towards them
+pub fn handle_camera_commands(
+    mut events: EventReader<CameraCommand>,
+    mut camera: Query<(&mut OrbitCameraController, &Projection)>,
+    atoms: Query<(&GlobalTransform, &Atom)>,
+    selected: Query<Entity, (With<Atom>, With<Selected>)>,
+    particles: Query<(&GlobalTransform, &Particle)>,
+    mut rig: ResMut<CameraRig>,
+) {
+    let Ok((mut controller, projection)) = camera.get_single_mut() else {
+        return;
+    };
+
+    for command in events.read() {
+        let focus = match *command {
+            CameraCommand::FocusSelected => selected.iter().next(),
+            CameraCommand::FocusAtom(entity) => Some(entity),
+            _ => None,
+        };
+        if let Some((transform, atom)) =
+            focus.and_then(|entity| atoms.get(entity).ok())
+        {
+            controller.target_focus = transform.translation();
+            controller.target_radius = atom.radius() * FOCUS_RADIUS_FACTOR;
+        }
+
+        match *command {
+            CameraCommand::FocusSelected | CameraCommand::FocusAtom(_) => {}
+            CameraCommand::ToggleFollow => {
+                rig.follow = match rig.follow {
+                    Some(_) => None,
+                    None => selected.iter().next(),
+                };
+            }
+            CameraCommand::FrameAll => {
+                let Some((focus, extent)) = bounding_sphere(&particles) else {
+                    continue;
+                };
+                // Distance at which a sphere of this size fills the
+                // narrower field of view
+                let fov = match projection {
+                    Projection::Perspective(perspective) => {
+                        let horizontal = 2.0
+                            * ((perspective.fov * 0.5).tan()
+                                * perspective.aspect_ratio)
+                                .atan();
+                        perspective.fov.min(horizontal)
+                    }
+                    Projection::Orthographic(_) => std::f32::consts::FRAC_PI_4,
+                };
+                rig.follow = None;
+                controller.target_focus = focus;
+                controller.target_radius =
+                    extent * FRAME_MARGIN / (fov * 0.5).sin();
+            }
+            CameraCommand::SaveBookmark(slot) => {
+                rig.bookmarks.insert(
+                    slot,
+                    CameraPose {
+                        focus: controller.target_focus,
+                        radius: controller.target_radius,
+                        yaw: controller.target_yaw,
+                        pitch: controller.target_pitch,
+                    },
+                );
+                info!("Saved camera bookmark {}", slot + 1);
+            }
+            CameraCommand::RestoreBookmark(slot) => {
+                let Some(pose) = rig.bookmarks.get(&slot).copied() else {
+                    continue;
+                };
+                rig.follow = None;
+                controller.target_focus = pose.focus;
+                controller.target_radius = pose.radius;
+                controller.target_yaw = pose.yaw;
+                controller.target_pitch = pose.pitch;
+            }
+        }
+    }
+}
+
+/// Keeps the followed atom at the orbit pivot, until it breaks apart
+pub fn follow_atom(
+    mut camera: Query<&mut OrbitCameraController>,
+    atoms: Query<&GlobalTransform, With<Atom>>,
+    mut rig: ResMut<CameraRig>,
+) {
+    let Some(entity) = rig.follow else {
+        return;
+    };
+    let Ok(transform) = atoms.get(entity) else {
+        rig.follow = None;
+        return;
+    };
+    if let Ok(mut controller) = camera.get_single_mut() {
+        controller.target_focus = transform.translation();
+    }
+}
+
+/// Centre and radius of a sphere around all particles
+fn bounding_sphere(
+    particles: &Query<(&GlobalTransform, &Particle)>,
+) -> Option<(Vec3, f32)> {
+    let count = particles.iter().len();
+    if count == 0 {
+        return None;
+    }
+    let centre = particles
+        .iter()
+        .map(|(transform, _)| transform.translation())
+        .sum::<Vec3>()
+        / count as f32;
+    let radius = particles
+        .iter()
+        .map(|(transform, particle)| {
+            transform.translation().distance(centre) + particle.radius
+        })
+        .fold(0.0, f32::max);
+    Some((centre, radius))
+}
@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::prelude::*;
+use bevy_dynamics::Velocity;
+use mechanics::particle::{GetColor, Kind, Particle};
+use mechanics::{Atom, Forces};
+use strum::IntoEnumIterator;
+
+use crate::{
+    ColorMode, ColorOverride, ColorScheme, ParticleAssets, ParticleColor,
+    ParticleLod,
+};
+
+/// Continuous schemes snap to this many colours, so the material cache only
+/// ever holds a handful of materials per scheme
+const GRADIENT_STEPS: f32 = 16.0;
+const LEGEND_STEPS: usize = 5;
+const FREE_COLOR: Color = Color::srgb(0.424, 0.439, 0.525); // Overlay0
+/// Catppuccin accents, for telling clusters apart and recolouring selections
+pub const PALETTE: [Color; 8] = [
+    Color::srgb(0.953, 0.545, 0.659), // Red
+    Color::srgb(0.980, 0.702, 0.529), // Peach
+    Color::srgb(0.976, 0.886, 0.686), // Yellow
+    Color::srgb(0.651, 0.890, 0.631), // Green
+    Color::srgb(0.580, 0.886, 0.835), // Teal
+    Color::srgb(0.537, 0.706, 0.980), // Blue
+    Color::srgb(0.796, 0.651, 0.969), // Mauve
+    Color::srgb(0.961, 0.761, 0.906), // Pink
+];
+
+// system that cycles the colour scheme on pressing C
+pub fn cycle_color_scheme(
+    mut scheme: ResMut<ColorScheme>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        scheme.mode = scheme.mode.next();
+    }
+}
+
+pub fn apply_color_scheme(
+    mut particles: Query<(
+        Entity,
+        &Particle,
+        &Velocity,
+        &Forces,
+        &ParticleLod,
+        Option<&ColorOverride>,
+        &mut ParticleColor,
+        &mut MeshMaterial3d<StandardMaterial>,
+    )>,
+    atoms: Query<(Entity, &Atom)>,
+    mut scheme: ResMut<ColorScheme>,
+    mut particle_assets: ResMut<ParticleAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let owners: HashMap<Entity, (Entity, &Atom)> = atoms
+        .iter()
+        .flat_map(|(entity, atom)| {
+            atom.constituent_particles
+                .iter()
+                .map(move |particle| (*particle, (entity, atom)))
+        })
+        .collect();
+
+    let mode = scheme.mode;
+    let value = |particle: &Particle, velocity: &Velocity, forces: &Forces| {
+        match mode {
+            ColorMode::Speed => velocity.value.length(),
+            ColorMode::KineticEnergy => {
+                0.5 * particle.mass * velocity.value.length_squared()
+            }
+            ColorMode::NetForce => forces.total().length(),
+            _ => 0.0,
+        }
+    };
+    // Continuous schemes range over what is currently on screen, rounded up
+    // so the legend only changes when the range does
+    let max = nice_ceiling(
+        particles
+            .iter()
+            .map(|(_, particle, velocity, forces, ..)| {
+                value(particle, velocity, forces)
+            })
+            .fold(f32::EPSILON, f32::max),
+    );
+
+    for (
+        entity,
+        particle,
+        velocity,
+        forces,
+        lod,
+        color_override,
+        mut color,
+        mut material,
+    ) in particles.iter_mut()
+    {
+        let scheme_color = match mode {
+            ColorMode::Kind => particle.get_color(),
+            ColorMode::Speed
+            | ColorMode::KineticEnergy
+            | ColorMode::NetForce => {
+                let t = value(particle, velocity, forces) / max;
+                gradient((t * GRADIENT_STEPS).round() / GRADIENT_STEPS)
+            }
+            ColorMode::Element => owners
+                .get(&entity)
+                .map_or(FREE_COLOR, |(_, atom)| atom.element.color()),
+            ColorMode::Cluster => {
+                owners.get(&entity).map_or(FREE_COLOR, |(atom, _)| {
+                    PALETTE[atom.index() as usize % PALETTE.len()]
+                })
+            }
+        };
+        // Recoloured particles keep their colour across schemes
+        let new_color = color_override.map_or(scheme_color, |color| color.0);
+
+        if color.0 != new_color {
+            color.0 = new_color;
+            material.0 =
+                particle_assets.material(new_color, *lod, &mut materials);
+        }
+    }
+
+    let legend = match mode {
+        ColorMode::Kind => Kind::iter()
+            .map(|kind| {
+                let color = Particle::from_kind(kind).get_color();
+                (format!("{kind:?}"), color)
+            })
+            .collect(),
+        ColorMode::Speed | ColorMode::KineticEnergy | ColorMode::NetForce => {
+            gradient_legend(max)
+        }
+        ColorMode::Element => {
+            let mut elements = BTreeMap::new();
+            for (_, atom) in atoms.iter() {
+                elements.insert(atom.element, atom.element.color());
+            }
+            elements
+                .into_iter()
+                .map(|(element, color)| (element.to_string(), color))
+                .chain([("Free".to_string(), FREE_COLOR)])
+                .collect()
+        }
+        ColorMode::Cluster => vec![
+            (format!("{} atoms", atoms.iter().len()), PALETTE[0]),
+            ("Free".to_string(), FREE_COLOR),
+        ],
+    };
+    // Only touch the legend when it differs, so the HUD rebuilds on change
+    if scheme.legend != legend {
+        scheme.legend = legend;
+    }
+}
+
+/// The smallest 1, 2 or 5 times a power of ten that is at least `value`
+fn nice_ceiling(value: f32) -> f32 {
+    let magnitude = 10.0_f32.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|step| step * magnitude)
+        .find(|nice| *nice >= value)
+        .unwrap_or(value)
+}
+
+/// Evenly spaced gradient samples labelled with their value, highest first
+fn gradient_legend(max: f32) -> Vec<(String, Color)> {
+    (0..LEGEND_STEPS)
+        .rev()
+        .map(|i| {
+            let t = i as f32 / (LEGEND_STEPS - 1) as f32;
+            (format!("{:.2}", t * max), gradient(t))
+        })
+        .collect()
+}
+
+/// Sequential map from blue at 0 through green and yellow to red at 1
+fn gradient(t: f32) -> Color {
+    const STOPS: [Srgba; 4] = [
+        Srgba::rgb(0.537, 0.706, 0.980), // Blue
+        Srgba::rgb(0.651, 0.890, 0.631), // Green
+        Srgba::rgb(0.976, 0.886, 0.686), // Yellow
+        Srgba::rgb(0.953, 0.545, 0.659), // Red
+    ];
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let index = (scaled as usize).min(STOPS.len() - 2);
+    STOPS[index]
+        .mix(&STOPS[index + 1], scaled - index as f32)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::nice_ceiling;
+
+    /// Equal up to the rounding of `powf`
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() <= expected * 1e-5,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn rounds_up_to_one_two_or_five() {
+        for (value, expected) in [
+            (1.0, 1.0),
+            (1.2, 2.0),
+            (3.0, 5.0),
+            (7.5, 10.0),
+            (11.0, 20.0),
+            (1234.0, 2000.0),
+            (4.0e5, 5.0e5),
+            (0.03, 0.05),
+            (0.15, 0.2),
+        ] {
+            assert_close(nice_ceiling(value), expected);
+        }
+    }
+
+    #[test]
+    fn keeps_values_that_are_already_nice() {
+        for value in [0.1, 2.0, 10.0, 100.0, 1000.0, 5.0e6] {
+            assert_close(nice_ceiling(value), value);
+        }
+    }
+
+    #[test]
+    fn never_rounds_down() {
+        let mut value = f32::EPSILON;
+        while value < 1.0e9 {
+            assert!(nice_ceiling(value) >= value, "{value} rounded down");
+            value *= 1.37;
+        }
+    }
+}
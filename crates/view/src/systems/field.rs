@@ -0,0 +1,234 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use mechanics::{particle::Particle, FieldKind, PhysicsConstants};
+
+use crate::{FieldSlice, FieldView};
+
+const FIELD_LINE_STEPS: usize = 100;
+const FIELD_LINE_STEP_LENGTH: f32 = 2.0;
+const FIELD_LINES_PER_PARTICLE: usize = 8;
+const FIELD_REFRESH_SECONDS: f32 = 0.2;
+
+pub fn setup_field_slice(
+    mut commands: Commands,
+    field_view: Res<FieldView>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let image = images.add(Image::new_fill(
+        Extent3d {
+            width: field_view.resolution,
+            height: field_view.resolution,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::new(Vec3::Y, Vec2::splat(0.5)))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(image.clone()),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        })),
+        field_view.slice_transform(),
+        Visibility::Hidden,
+        FieldSlice { image },
+        PickingBehavior::IGNORE,
+    ));
+}
+
+// system that cycles the sampled field on pressing F, moves the slice with
+// PageUp/PageDown, turns it with Home and toggles field lines on pressing L
+pub fn control_field_view(
+    mut field_view: ResMut<FieldView>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyF) {
+        field_view.field = match field_view.field {
+            None => Some(FieldKind::Electric),
+            Some(FieldKind::Electric) => Some(FieldKind::Gravitational),
+            Some(FieldKind::Gravitational) => Some(FieldKind::Strong),
+            Some(FieldKind::Strong) => None,
+        };
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Home) {
+        field_view.normal = if field_view.normal == Dir3::Y {
+            Dir3::Z
+        } else if field_view.normal == Dir3::Z {
+            Dir3::X
+        } else {
+            Dir3::Y
+        };
+    }
+
+    let speed = field_view.extent * 0.25 * time.delta_secs();
+    if keyboard_input.pressed(KeyCode::PageUp) {
+        field_view.offset += speed;
+    }
+    if keyboard_input.pressed(KeyCode::PageDown) {
+        field_view.offset -= speed;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        field_view.field_lines = !field_view.field_lines;
+    }
+}
+
+pub fn update_field_slice(
+    mut slice: Query<(&FieldSlice, &mut Transform, &mut Visibility)>,
+    particles: Query<(&Transform, &Particle), Without<FieldSlice>>,
+    field_view: Res<FieldView>,
+    physics: Res<PhysicsConstants>,
+    mut images: ResMut<Assets<Image>>,
+    time: Res<Time>,
+    mut since_refresh: Local<f32>,
+) {
+    let Ok((slice, mut transform, mut visibility)) = slice.get_single_mut()
+    else {
+        return;
+    };
+
+    let Some(field) = field_view.field else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    *transform = field_view.slice_transform();
+
+    // Sampling is O(pixels * particles), so it only refreshes a few times a
+    // second unless the slice itself changed
+    *since_refresh += time.delta_secs();
+    if *since_refresh < FIELD_REFRESH_SECONDS
+        && !field_view.is_changed()
+        && !physics.is_changed()
+    {
+        return;
+    }
+    *since_refresh = 0.0;
+    let Some(image) = images.get_mut(&slice.image) else {
+        return;
+    };
+
+    let resolution = field_view.resolution as usize;
+    let sources: Vec<(Vec3, &Particle)> = particles
+        .iter()
+        .map(|(transform, particle)| (transform.translation, particle))
+        .collect();
+    let samples: Vec<f32> = (0..resolution * resolution)
+        .map(|i| {
+            // Texel centres, laid out like the UVs of the plane mesh
+            let u = ((i % resolution) as f32 + 0.5) / resolution as f32;
+            let v = ((i / resolution) as f32 + 0.5) / resolution as f32;
+            let point =
+                transform.transform_point(Vec3::new(u - 0.5, 0.0, v - 0.5));
+            sources
+                .iter()
+                .map(|(position, particle)| {
+                    physics.potential(
+                        field,
+                        particle,
+                        point.distance(*position),
+                    )
+                })
+                .sum()
+        })
+        .collect();
+
+    // Potentials span orders of magnitude near particles, so compress them
+    // logarithmically before normalising
+    let compressed: Vec<f32> = samples
+        .iter()
+        .map(|sample| sample.signum() * sample.abs().ln_1p())
+        .collect();
+    let max = compressed
+        .iter()
+        .fold(f32::EPSILON, |max, sample| max.max(sample.abs()));
+
+    image.data.clear();
+    for sample in compressed {
+        let color = colormap(sample / max).to_srgba().to_u8_array();
+        image.data.extend_from_slice(&color);
+    }
+}
+
+/// Diverging map: blue for negative, transparent around zero, red for
+/// positive potential
+fn colormap(value: f32) -> Color {
+    let value = value.clamp(-1.0, 1.0);
+    let (r, g, b) = if value >= 0.0 {
+        (243, 139, 168) // Red
+    } else {
+        (137, 180, 250) // Blue
+    };
+    Color::srgba_u8(r, g, b, (value.abs() * 220.0) as u8)
+}
+
+pub fn draw_field_lines(
+    mut gizmos: Gizmos,
+    particles: Query<(&Transform, &Particle)>,
+    field_view: Res<FieldView>,
+    physics: Res<PhysicsConstants>,
+) {
+    if !field_view.field_lines {
+        return;
+    }
+
+    let sources: Vec<(Vec3, &Particle)> = particles
+        .iter()
+        .filter(|(_, particle)| particle.charge.charge() != 0.0)
+        .map(|(transform, particle)| (transform.translation, particle))
+        .collect();
+    let field_at = |point: Vec3| -> Vec3 {
+        sources
+            .iter()
+            .map(|(position, particle)| {
+                physics.electric_field(particle, point - *position)
+            })
+            .sum()
+    };
+
+    for (position, particle) in &sources {
+        // Lines leave positive charges and enter negative ones, so trace
+        // against the field from negative charges
+        let sign = particle.charge.charge().signum();
+        for i in 0..FIELD_LINES_PER_PARTICLE {
+            let direction = fibonacci_direction(i, FIELD_LINES_PER_PARTICLE);
+            let mut point = *position + direction * particle.radius * 1.5;
+            let mut line = Vec::with_capacity(FIELD_LINE_STEPS);
+            line.push(point);
+            for _ in 0..FIELD_LINE_STEPS {
+                let step = field_at(point).normalize_or_zero() * sign;
+                if step == Vec3::ZERO {
+                    break;
+                }
+                point += step * FIELD_LINE_STEP_LENGTH;
+                line.push(point);
+            }
+            gizmos.linestrip(line, Color::srgba_u8(249, 226, 175, 120));
+        }
+    }
+}
+
+/// Evenly spread directions on the unit sphere
+fn fibonacci_direction(index: usize, count: usize) -> Vec3 {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+    let y = 1.0 - 2.0 * (index as f32 + 0.5) / count as f32;
+    let radius = (1.0 - y * y).sqrt();
+    let theta = golden_angle * index as f32;
+    Vec3::new(radius * theta.cos(), y, radius * theta.sin())
+}
@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+
+use bevy::{
+    prelude::*,
+    render::{camera::RenderTarget, view::RenderLayers},
+};
+use mechanics::particle::{GetColor, Particle};
+use mechanics::{Atom, PhysicsConstants, Selected};
+
+use crate::{
+    InspectorCamera, NucleusInspector, NucleusProxy, ParticleAssets,
+    ParticleLod,
+};
+
+/// Render layer of the nucleus proxies, kept out of the main camera
+pub const INSPECTOR_LAYER: usize = 1;
+/// How far nucleons are pushed apart from the centre of the nucleus
+const EXPLODE_FACTOR: f32 = 1.8;
+/// Camera distance per unit of nucleus extent
+const CAMERA_DISTANCE_FACTOR: f32 = 3.0;
+/// Radians per second the inspector camera circles the nucleus
+const ORBIT_SPEED: f32 = 0.3;
+
+/// Gizmos drawn only into the nucleus inspector
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct InspectorGizmos;
+
+pub fn setup_nucleus_inspector(
+    mut commands: Commands,
+    inspector: Res<NucleusInspector>,
+    mut gizmo_config: ResMut<GizmoConfigStore>,
+) {
+    commands.spawn((
+        Camera {
+            target: RenderTarget::Image(inspector.image.clone()),
+            order: -1,
+            is_active: false,
+            clear_color: ClearColorConfig::Custom(Color::srgba_u8(
+                30, 30, 46, 0,
+            )),
+            ..default()
+        },
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 50.0).looking_at(Vec3::ZERO, Vec3::Y),
+        RenderLayers::layer(INSPECTOR_LAYER),
+        InspectorCamera,
+    ));
+
+    commands.spawn((
+        Transform::from_translation(Vec3::new(0.0, 10.0, 10.0))
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        DirectionalLight {
+            illuminance: 5000.0,
+            ..default()
+        },
+        RenderLayers::layer(INSPECTOR_LAYER),
+    ));
+
+    let (config, _) = gizmo_config.config_mut::<InspectorGizmos>();
+    config.render_layers = RenderLayers::layer(INSPECTOR_LAYER);
+    config.line_width = 3.0;
+}
+
+// system that toggles the nucleus inspector on pressing I
+pub fn toggle_nucleus_inspector(
+    mut inspector: ResMut<NucleusInspector>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyI) {
+        inspector.enabled = !inspector.enabled;
+    }
+}
+
+/// Keeps one proxy per nucleon of the selected atom, exploded around the
+/// origin, with the inspector camera circling them
+pub fn sync_nucleus_inspector(
+    mut commands: Commands,
+    mut inspector: ResMut<NucleusInspector>,
+    selected: Query<(Entity, &Atom, &GlobalTransform), With<Selected>>,
+    particles: Query<(&GlobalTransform, &Particle)>,
+    mut proxies: Query<
+        (Entity, &NucleusProxy, &mut Transform),
+        Without<InspectorCamera>,
+    >,
+    mut camera: Query<(&mut Camera, &mut Transform), With<InspectorCamera>>,
+    mut particle_assets: ResMut<ParticleAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    let Ok((mut camera, mut camera_transform)) = camera.get_single_mut() else {
+        return;
+    };
+    let target = selected.iter().next().filter(|_| inspector.enabled);
+    camera.is_active = target.is_some();
+
+    let Some((atom_entity, atom, atom_transform)) = target else {
+        for (entity, ..) in proxies.iter() {
+            commands.entity(entity).despawn();
+        }
+        if inspector.atom.is_some() {
+            inspector.atom = None;
+        }
+        return;
+    };
+
+    // Nucleons join and leave the atom, so rebuild the proxies whenever the
+    // set of constituents no longer matches
+    let shown: HashSet<Entity> =
+        proxies.iter().map(|(_, proxy, _)| proxy.source).collect();
+    let constituents: HashSet<Entity> =
+        atom.constituent_particles.iter().copied().collect();
+    if inspector.atom != Some(atom_entity) || shown != constituents {
+        for (entity, ..) in proxies.iter() {
+            commands.entity(entity).despawn();
+        }
+        for (source, (_, particle)) in constituents
+            .iter()
+            .filter_map(|source| Some((*source, particles.get(*source).ok()?)))
+        {
+            commands.spawn((
+                NucleusProxy {
+                    source,
+                    kind: particle.kind,
+                },
+                Mesh3d(particle_assets.mesh(ParticleLod::Full)),
+                MeshMaterial3d(particle_assets.material(
+                    particle.get_color(),
+                    ParticleLod::Full,
+                    &mut materials,
+                )),
+                Transform::from_scale(Vec3::splat(particle.radius)),
+                RenderLayers::layer(INSPECTOR_LAYER),
+                PickingBehavior::IGNORE,
+            ));
+        }
+        inspector.atom = Some(atom_entity);
+        return;
+    }
+
+    let centre = atom_transform.translation();
+    let mut extent: f32 = 1.0;
+    for (_, proxy, mut transform) in proxies.iter_mut() {
+        let Ok((source, particle)) = particles.get(proxy.source) else {
+            continue;
+        };
+        transform.translation =
+            (source.translation() - centre) * EXPLODE_FACTOR;
+        extent = extent.max(transform.translation.length() + particle.radius);
+    }
+
+    let angle = time.elapsed_secs() * ORBIT_SPEED;
+    let distance = extent * CAMERA_DISTANCE_FACTOR;
+    *camera_transform = Transform::from_xyz(
+        angle.sin() * distance,
+        distance * 0.3,
+        angle.cos() * distance,
+    )
+    .looking_at(Vec3::ZERO, Vec3::Y);
+}
+
+/// Connects the proxies of nucleons that are currently within equilibrium
+/// distance of each other, where the strong force binds them
+pub fn draw_nucleus_bonds(
+    mut gizmos: Gizmos<InspectorGizmos>,
+    proxies: Query<(&NucleusProxy, &Transform)>,
+    particles: Query<&GlobalTransform, With<Particle>>,
+    physics: Res<PhysicsConstants>,
+) {
+    let nucleons: Vec<(Vec3, Vec3)> = proxies
+        .iter()
+        .filter_map(|(proxy, transform)| {
+            let source = particles.get(proxy.source).ok()?;
+            Some((source.translation(), transform.translation))
+        })
+        .collect();
+
+    for (i, (source_a, proxy_a)) in nucleons.iter().enumerate() {
+        for (source_b, proxy_b) in &nucleons[i + 1..] {
+            if source_a.distance(*source_b) < physics.equilibrium_distance {
+                gizmos.line(
+                    *proxy_a,
+                    *proxy_b,
+                    Color::srgba_u8(249, 226, 175, 200),
+                );
+            }
+        }
+    }
+}
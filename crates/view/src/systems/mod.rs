@@ -0,0 +1,461 @@
+mod camera;
+mod color;
+mod field;
+mod inspector;
+mod orbital;
+mod screenshot;
+mod selection;
+mod tools;
+
+pub use camera::*;
+pub use color::*;
+pub use field::*;
+pub use inspector::*;
+pub use orbital::*;
+pub use screenshot::*;
+pub use selection::*;
+pub use tools::*;
+
+use bevy::{ecs::system::SystemParam, picking::focus::HoverMap, prelude::*};
+use bevy_blendy_cameras::{FlyCameraController, OrbitCameraController};
+use bevy_dynamics::Debug;
+use mechanics::particle::{GetColor, Kind, Particle};
+use mechanics::{Atom, Selected};
+
+use crate::{
+    AtomHitbox, ElectronCloud, Highlighted, LodPolicy, MainCamera, Marquee,
+    ParticleAssets, ParticleColor, ParticleInspector, ParticleLod,
+    ParticleView, TextInputFocus, ToolMode, Tools, Trail, TrailSettings,
+};
+
+const UNSELECTED_ALPHA: f32 = 20.0 / 255.0;
+const SELECTED_ALPHA: f32 = 100.0 / 255.0;
+
+/// The electron cloud replaces the hitbox sphere as the visible shell of an
+/// atom, so the hitbox only shows when selected or hovered
+fn resting_alpha(cloud: bool) -> f32 {
+    if cloud {
+        0.0
+    } else {
+        UNSELECTED_ALPHA
+    }
+}
+
+/// Run condition for keyboard shortcuts, false while a text field has focus
+pub fn keyboard_free(focus: Res<TextInputFocus>) -> bool {
+    focus.0.is_none()
+}
+
+pub fn setup_view(
+    mut commands: Commands,
+    // mut meshes: ResMut<Assets<Mesh>>,
+    // mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // spawn lighting
+    // - directional light
+    commands.spawn((
+        Transform::from_translation(Vec3::new(0.0, 10.0, 10.0))
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        DirectionalLight {
+            illuminance: 5000.0,
+            ..default()
+        },
+    ));
+
+    // spawn camera
+    commands.spawn((
+        Camera {
+            order: 0,
+            ..default()
+        },
+        Camera3d::default(),
+        Transform::from_translation(Vec3::new(0.0, 100.0, 0.0))
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        OrbitCameraController::default(),
+        FlyCameraController {
+            is_enabled: false,
+            ..default()
+        },
+        MainCamera,
+    ));
+}
+
+pub fn init_particles(
+    mut commands: Commands,
+    mut query: Query<(Entity, &Particle, &mut Transform), Added<Particle>>,
+    mut particle_assets: ResMut<ParticleAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, particle, mut transform) in query.iter_mut() {
+        transform.scale = Vec3::splat(particle.radius);
+        // Starts out coloured by kind, `apply_color_scheme` takes over from
+        // the next frame
+        let color = particle.get_color();
+        commands
+            .entity(entity)
+            .insert((
+                ParticleView,
+                ParticleLod::Full,
+                ParticleColor(color),
+                Mesh3d(particle_assets.mesh(ParticleLod::Full)),
+                MeshMaterial3d(particle_assets.material(
+                    color,
+                    ParticleLod::Full,
+                    &mut materials,
+                )),
+            ))
+            .observe(over_particle)
+            .observe(out_particle)
+            .observe(click_particle);
+    }
+}
+
+pub fn update_particle_lod(
+    camera: Query<&GlobalTransform, With<OrbitCameraController>>,
+    mut particles: Query<
+        (
+            &ParticleColor,
+            &mut Transform,
+            &mut ParticleLod,
+            &mut Mesh3d,
+            &mut MeshMaterial3d<StandardMaterial>,
+        ),
+        With<ParticleView>,
+    >,
+    policy: Res<LodPolicy>,
+    mut particle_assets: ResMut<ParticleAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    let camera_position = camera.translation();
+    let camera_rotation = camera.rotation();
+
+    for (color, mut transform, mut lod, mut mesh, mut material) in
+        particles.iter_mut()
+    {
+        let level =
+            policy.level(transform.translation.distance(camera_position));
+        if *lod != level {
+            *lod = level;
+            mesh.0 = particle_assets.mesh(level);
+            material.0 =
+                particle_assets.material(color.0, level, &mut materials);
+        }
+
+        // Discs face +Z, so sharing the camera's rotation turns them to face it
+        if level == ParticleLod::Impostor {
+            transform.rotation = camera_rotation;
+        }
+    }
+}
+
+/// Adds and removes [`Trail`]s to match the [`TrailSettings`]
+pub fn sync_trails(
+    mut commands: Commands,
+    particles: Query<(Entity, &Particle, Has<Trail>), With<ParticleView>>,
+    settings: Res<TrailSettings>,
+) {
+    for (entity, particle, has_trail) in particles.iter() {
+        match (settings.shows(particle.kind), has_trail) {
+            (true, false) => {
+                commands.entity(entity).insert(Trail::default());
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Trail>();
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn record_trails(
+    mut trails: Query<(&Transform, &mut Trail)>,
+    settings: Res<TrailSettings>,
+) {
+    for (transform, mut trail) in trails.iter_mut() {
+        trail.positions.push_back(transform.translation);
+        while trail.positions.len() > settings.length {
+            trail.positions.pop_front();
+        }
+    }
+}
+
+pub fn draw_trails(
+    mut gizmos: Gizmos,
+    trails: Query<(&ParticleColor, &Trail)>,
+) {
+    for (color, trail) in trails.iter() {
+        let color = color.0;
+        let count = trail.positions.len().max(1) as f32;
+        gizmos.linestrip_gradient(trail.positions.iter().enumerate().map(
+            |(i, position)| (*position, color.with_alpha(i as f32 / count)),
+        ));
+    }
+}
+
+// system that toggles trails on pressing T, and the trail of each kind on
+// pressing Shift + 1-4
+pub fn toggle_trails(
+    mut settings: ResMut<TrailSettings>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyT) {
+        settings.enabled = !settings.enabled;
+    }
+
+    if keyboard_input.pressed(KeyCode::ShiftLeft)
+        || keyboard_input.pressed(KeyCode::ShiftRight)
+    {
+        for (key, kind) in [
+            (KeyCode::Digit1, Kind::Proton),
+            (KeyCode::Digit2, Kind::Neutron),
+            (KeyCode::Digit3, Kind::Electron),
+            (KeyCode::Digit4, Kind::Photon),
+        ] {
+            if keyboard_input.just_pressed(key) {
+                settings.toggle_kind(kind);
+            }
+        }
+    }
+}
+
+// system that toggles debug on pressing U
+pub fn toggle_debug(
+    mut query: Query<&mut Debug>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyU) {
+        for mut debug in query.iter_mut() {
+            debug.acceleration = !debug.acceleration;
+            debug.velocity = !debug.velocity;
+        }
+    }
+}
+
+pub fn maintain_atom_hitbox_colour(
+    query: Query<
+        (
+            &Atom,
+            &MeshMaterial3d<StandardMaterial>,
+            Has<Selected>,
+            Has<Highlighted>,
+            Has<ElectronCloud>,
+        ),
+        With<AtomHitbox>,
+    >,
+    changed: Query<
+        Entity,
+        (
+            With<AtomHitbox>,
+            Or<(
+                Changed<Atom>,
+                Added<Selected>,
+                Added<Highlighted>,
+                Added<ElectronCloud>,
+            )>,
+        ),
+    >,
+    mut deselected: RemovedComponents<Selected>,
+    mut unhighlighted: RemovedComponents<Highlighted>,
+    mut cloud_removed: RemovedComponents<ElectronCloud>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for entity in changed
+        .iter()
+        .chain(deselected.read())
+        .chain(unhighlighted.read())
+        .chain(cloud_removed.read())
+    {
+        let Ok((atom, material, selected, highlighted, cloud)) =
+            query.get(entity)
+        else {
+            continue;
+        };
+        if let Some(material) = materials.get_mut(&material.0) {
+            let alpha = if selected || highlighted {
+                SELECTED_ALPHA
+            } else {
+                resting_alpha(cloud)
+            };
+            material.base_color = atom.element.color().with_alpha(alpha);
+        }
+    }
+}
+
+pub fn spawn_atom_hitbox(
+    mut commands: Commands,
+    query: Query<(Entity, &Atom, Has<Selected>), Added<Atom>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, atom, selected) in query.iter() {
+        commands
+            .entity(entity)
+            .insert((
+                AtomHitbox,
+                Mesh3d(meshes.add(Sphere::new(1.0).mesh().ico(10).unwrap())),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: match selected {
+                        true => atom.element.color().with_alpha(SELECTED_ALPHA),
+                        false => {
+                            atom.element.color().with_alpha(UNSELECTED_ALPHA)
+                        }
+                    },
+                    alpha_mode: AlphaMode::Blend,
+                    ..default()
+                })),
+                // Lets the nucleons inside be hovered and clicked too
+                PickingBehavior {
+                    should_block_lower: false,
+                    is_hoverable: true,
+                },
+            ))
+            .observe(over_atom)
+            .observe(out_atom)
+            .observe(click_atom);
+    }
+}
+
+fn over_atom(
+    trigger: Trigger<Pointer<Over>>,
+    query: Query<&MeshMaterial3d<StandardMaterial>, Without<Selected>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if let Ok(material) = query.get(trigger.entity()) {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color.set_alpha(SELECTED_ALPHA);
+        }
+    }
+}
+
+fn out_atom(
+    trigger: Trigger<Pointer<Out>>,
+    query: Query<
+        (&MeshMaterial3d<StandardMaterial>, Has<ElectronCloud>),
+        (Without<Selected>, Without<Highlighted>),
+    >,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if let Ok((material, cloud)) = query.get(trigger.entity()) {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color.set_alpha(resting_alpha(cloud));
+        }
+    }
+}
+
+/// What a click on an atom or particle does, from the active tool, the
+/// marquee and the modifier keys
+#[derive(SystemParam)]
+struct SceneClick<'w> {
+    tools: Res<'w, Tools>,
+    marquee: Res<'w, Marquee>,
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    hover_map: Res<'w, HoverMap>,
+}
+
+impl SceneClick<'_> {
+    /// Only left clicks with the select tool pick, and not the end of a
+    /// marquee drag
+    fn picks(&self, click: &Pointer<Click>) -> bool {
+        click.button == PointerButton::Primary
+            && self.tools.mode == ToolMode::Select
+            && !self.marquee.is_dragging()
+    }
+
+    /// Whether Shift is held, which adds to the selection instead of
+    /// replacing it
+    fn additive(&self) -> bool {
+        self.keyboard_input.pressed(KeyCode::ShiftLeft)
+            || self.keyboard_input.pressed(KeyCode::ShiftRight)
+    }
+
+    /// Whether Ctrl is held, which picks a nucleon inside an atom instead of
+    /// the atom
+    fn inspecting(&self) -> bool {
+        self.keyboard_input.pressed(KeyCode::ControlLeft)
+            || self.keyboard_input.pressed(KeyCode::ControlRight)
+    }
+
+    /// Whether the click also went through anything `matches` accepts
+    fn hit(
+        &self,
+        click: &Pointer<Click>,
+        matches: impl Fn(Entity) -> bool,
+    ) -> bool {
+        self.hover_map
+            .get(&click.pointer_id)
+            .is_some_and(|hits| hits.keys().any(|hit| matches(*hit)))
+    }
+}
+
+fn click_atom(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    selected: Query<Entity, With<Selected>>,
+    particles: Query<(), With<Particle>>,
+    click: SceneClick,
+) {
+    if !click.picks(&trigger) {
+        return;
+    }
+    // with Ctrl, a click on one of its nucleons pins that nucleon instead
+    if click.inspecting() && click.hit(&trigger, |hit| particles.contains(hit))
+    {
+        return;
+    }
+
+    toggle_selection(
+        &mut commands,
+        &selected,
+        trigger.entity(),
+        click.additive(),
+    );
+}
+
+fn over_particle(
+    trigger: Trigger<Pointer<Over>>,
+    mut inspector: ResMut<ParticleInspector>,
+) {
+    inspector.hovered = Some(trigger.entity());
+}
+
+fn out_particle(
+    trigger: Trigger<Pointer<Out>>,
+    mut inspector: ResMut<ParticleInspector>,
+) {
+    if inspector.hovered == Some(trigger.entity()) {
+        inspector.hovered = None;
+    }
+}
+
+/// Pins the clicked particle, or with Shift adds it to the selection. Inside
+/// an atom the atom takes the click, unless Ctrl is held.
+fn click_particle(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut inspector: ResMut<ParticleInspector>,
+    selected: Query<Entity, With<Selected>>,
+    hitboxes: Query<(), With<AtomHitbox>>,
+    click: SceneClick,
+) {
+    if !click.picks(&trigger) {
+        return;
+    }
+    if !click.inspecting() && click.hit(&trigger, |hit| hitboxes.contains(hit))
+    {
+        return;
+    }
+
+    let entity = trigger.entity();
+    if click.additive() {
+        toggle_selection(&mut commands, &selected, entity, true);
+        return;
+    }
+    inspector.pinned = if inspector.pinned == Some(entity) {
+        None
+    } else {
+        Some(entity)
+    };
+}
@@ -0,0 +1,335 @@
+use bevy::{
+    prelude::*,
+    render::{mesh::PrimitiveTopology, render_asset::RenderAssetUsages},
+};
+use mechanics::Atom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{ElectronCloud, OrbitalClouds, OrbitalView};
+
+/// Subshells as (n, l) in Madelung filling order
+const AUFBAU: [(u32, u32); 19] = [
+    (1, 0),
+    (2, 0),
+    (2, 1),
+    (3, 0),
+    (3, 1),
+    (4, 0),
+    (3, 2),
+    (4, 1),
+    (5, 0),
+    (4, 2),
+    (5, 1),
+    (6, 0),
+    (4, 3),
+    (5, 2),
+    (6, 1),
+    (7, 0),
+    (5, 3),
+    (6, 2),
+    (7, 1),
+];
+/// Radius of the outermost shell in atom radii, far from physical (that
+/// would be ~10^5) but keeps clouds and nuclei readable together
+const CLOUD_RADII: f32 = 3.0;
+const BURN_IN_STEPS: usize = 500;
+const STEPS_PER_POINT: usize = 5;
+
+/// One real hydrogen-like orbital, `m` indexing the real harmonics of `l`
+#[derive(Debug, Clone, Copy)]
+struct Orbital {
+    n: u32,
+    l: u32,
+    m: usize,
+}
+
+// system that toggles electron clouds on pressing O, and drawing neutral
+// atoms regardless of their bound electrons on pressing Shift + O
+pub fn toggle_orbitals(
+    mut settings: ResMut<OrbitalView>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyO) {
+        if keyboard_input.pressed(KeyCode::ShiftLeft)
+            || keyboard_input.pressed(KeyCode::ShiftRight)
+        {
+            settings.assume_neutral = !settings.assume_neutral;
+        } else {
+            settings.enabled = !settings.enabled;
+        }
+    }
+}
+
+/// Spawns, swaps and removes the point cloud child of each atom to match
+/// its electron count
+pub fn sync_electron_clouds(
+    mut commands: Commands,
+    atoms: Query<(Entity, &Atom, Option<&ElectronCloud>)>,
+    settings: Res<OrbitalView>,
+    mut clouds: ResMut<OrbitalClouds>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, atom, cloud) in atoms.iter() {
+        let electron_count = match settings.enabled {
+            false => 0,
+            true if settings.assume_neutral => atom.proton_count,
+            true => atom.electron_count,
+        };
+        if cloud.map(|cloud| cloud.electron_count) == Some(electron_count) {
+            continue;
+        }
+
+        if let Some(cloud) = cloud {
+            commands.entity(cloud.points).despawn_recursive();
+        }
+        if electron_count == 0 {
+            if cloud.is_some() {
+                commands.entity(entity).remove::<ElectronCloud>();
+            }
+            continue;
+        }
+
+        let mesh = clouds
+            .meshes
+            .entry(electron_count)
+            .or_insert_with(|| {
+                meshes.add(cloud_mesh(
+                    electron_count,
+                    settings.points_per_electron,
+                ))
+            })
+            .clone();
+        let points = commands
+            .spawn((
+                Mesh3d(mesh),
+                MeshMaterial3d(clouds.material.clone()),
+                // Scaled with `Atom::radius` through the atom's transform
+                Transform::from_scale(Vec3::splat(CLOUD_RADII)),
+                PickingBehavior::IGNORE,
+            ))
+            .set_parent(entity)
+            .id();
+        commands.entity(entity).insert(ElectronCloud {
+            electron_count,
+            points,
+        });
+    }
+}
+
+/// Point cloud of all occupied orbitals for a ground state configuration,
+/// in units of the outermost shell. Seeded by the electron count, so every
+/// atom with that count shares the same cloud.
+fn cloud_mesh(electron_count: u32, points_per_electron: usize) -> Mesh {
+    let mut rng = ChaCha8Rng::seed_from_u64(u64::from(electron_count));
+    let orbitals = occupied_orbitals(electron_count);
+    let outer_n = orbitals.iter().map(|(orbital, _)| orbital.n).max();
+    let scale = 1.0 / shell_extent(outer_n.unwrap_or(1));
+
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    for (orbital, occupancy) in orbitals {
+        let color = orbital_color(orbital.l).to_linear().to_f32_array();
+        for point in
+            sample_orbital(orbital, points_per_electron * occupancy, &mut rng)
+        {
+            positions.push((point * scale).to_array());
+            colors.push(color);
+        }
+    }
+
+    Mesh::new(PrimitiveTopology::PointList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+}
+
+/// Orbitals and their electron counts, filling subshells in [`AUFBAU`]
+/// order and each subshell by Hund's rule. Only s, p and d shapes are drawn,
+/// so electrons in f subshells are left out.
+fn occupied_orbitals(electron_count: u32) -> Vec<(Orbital, usize)> {
+    let mut remaining = electron_count;
+    let mut orbitals = Vec::new();
+    for (n, l) in AUFBAU {
+        if remaining == 0 {
+            break;
+        }
+        let count = 2 * l + 1;
+        let electrons = remaining.min(2 * count);
+        remaining -= electrons;
+        if l > 2 {
+            continue;
+        }
+        for m in 0..count {
+            // Every orbital takes one electron before any takes a second
+            let occupancy =
+                u32::from(m < electrons) + u32::from(m + count < electrons);
+            if occupancy > 0 {
+                orbitals.push((
+                    Orbital {
+                        n,
+                        l,
+                        m: m as usize,
+                    },
+                    occupancy as usize,
+                ));
+            }
+        }
+    }
+    orbitals
+}
+
+/// Samples `count` points from the probability density of `orbital` with
+/// a Metropolis walk, in Bohr radii
+fn sample_orbital(
+    orbital: Orbital,
+    count: usize,
+    rng: &mut impl Rng,
+) -> Vec<Vec3> {
+    let step = orbital.n as f32;
+    let mut position = Vec3::splat(step);
+    let mut density = probability_density(orbital, position);
+    let mut points = Vec::with_capacity(count);
+
+    for i in 0..BURN_IN_STEPS + count * STEPS_PER_POINT {
+        let proposal = position
+            + Vec3::new(
+                rng.gen_range(-step..step),
+                rng.gen_range(-step..step),
+                rng.gen_range(-step..step),
+            );
+        let proposal_density = probability_density(orbital, proposal);
+        if proposal_density >= density * rng.gen::<f32>() {
+            position = proposal;
+            density = proposal_density;
+        }
+        if i >= BURN_IN_STEPS && (i - BURN_IN_STEPS) % STEPS_PER_POINT == 0 {
+            points.push(position);
+        }
+    }
+    points
+}
+
+/// |ψ|² of a hydrogen-like orbital, up to a constant factor
+fn probability_density(orbital: Orbital, position: Vec3) -> f32 {
+    let Orbital { n, l, m } = orbital;
+    let r = position.length();
+    let rho = 2.0 * r / n as f32;
+    let radial = rho.powi(l as i32)
+        * (-rho / 2.0).exp()
+        * laguerre(n - l - 1, (2 * l + 1) as f32, rho);
+    let angular = real_harmonic(l, m, position.normalize_or_zero());
+    (radial * angular).powi(2)
+}
+
+/// Generalized Laguerre polynomial L_k^α(x) by its recurrence
+fn laguerre(k: u32, alpha: f32, x: f32) -> f32 {
+    let (mut previous, mut current) = (1.0, 1.0 + alpha - x);
+    if k == 0 {
+        return previous;
+    }
+    for i in 1..k {
+        let i = i as f32;
+        let next = ((2.0 * i + 1.0 + alpha - x) * current
+            - (i + alpha) * previous)
+            / (i + 1.0);
+        previous = current;
+        current = next;
+    }
+    current
+}
+
+/// Real spherical harmonics up to a constant factor, for unit `direction`
+fn real_harmonic(l: u32, m: usize, direction: Vec3) -> f32 {
+    let Vec3 { x, y, z } = direction;
+    match (l, m) {
+        (0, _) => 1.0,
+        (1, 0) => x,
+        (1, 1) => y,
+        (1, _) => z,
+        (2, 0) => x * y,
+        (2, 1) => y * z,
+        (2, 2) => (3.0 * z * z - 1.0) / 3.0_f32.sqrt(),
+        (2, 3) => x * z,
+        (2, _) => (x * x - y * y) / 2.0,
+        _ => 0.0,
+    }
+}
+
+/// Radius that holds nearly all of the density of shell `n`, in Bohr radii
+fn shell_extent(n: u32) -> f32 {
+    let n = n as f32;
+    2.0 * n * n + 2.0
+}
+
+fn orbital_color(l: u32) -> Color {
+    match l {
+        0 => Color::srgba_u8(137, 180, 250, 140), // s: blue
+        1 => Color::srgba_u8(166, 227, 161, 140), // p: green
+        _ => Color::srgba_u8(203, 166, 247, 140), // d: mauve
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::occupied_orbitals;
+
+    /// `(n, l, m, electrons)` of every occupied orbital
+    fn configuration(electron_count: u32) -> Vec<(u32, u32, usize, usize)> {
+        occupied_orbitals(electron_count)
+            .into_iter()
+            .map(|(orbital, electrons)| {
+                (orbital.n, orbital.l, orbital.m, electrons)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fills_subshells_in_aufbau_order() {
+        assert!(configuration(0).is_empty());
+        assert_eq!(configuration(1), [(1, 0, 0, 1)]);
+        assert_eq!(configuration(2), [(1, 0, 0, 2)]);
+        // Sodium: 1s2 2s2 2p6 3s1
+        let sodium = configuration(11);
+        assert_eq!(sodium.first(), Some(&(1, 0, 0, 2)));
+        assert_eq!(sodium.last(), Some(&(3, 0, 0, 1)));
+        // Potassium fills 4s before 3d
+        assert_eq!(configuration(19).last(), Some(&(4, 0, 0, 1)));
+        assert_eq!(configuration(21).last(), Some(&(3, 2, 0, 1)));
+    }
+
+    #[test]
+    fn spreads_electrons_by_hunds_rule() {
+        // Carbon: the two 2p electrons are unpaired
+        assert_eq!(configuration(6)[2..], [(2, 1, 0, 1), (2, 1, 1, 1)]);
+        // Nitrogen: one electron in each 2p orbital
+        assert_eq!(
+            configuration(7)[2..],
+            [(2, 1, 0, 1), (2, 1, 1, 1), (2, 1, 2, 1)]
+        );
+        // Oxygen: only then does the first one pair up
+        assert_eq!(
+            configuration(8)[2..],
+            [(2, 1, 0, 2), (2, 1, 1, 1), (2, 1, 2, 1)]
+        );
+    }
+
+    #[test]
+    fn places_every_electron_up_to_the_f_block() {
+        for count in 0..=56 {
+            let placed: usize = configuration(count)
+                .iter()
+                .map(|(.., electrons)| electrons)
+                .sum();
+            assert_eq!(placed, count as usize);
+        }
+        // 4f comes next and isn't drawn
+        let placed: usize = configuration(70)
+            .iter()
+            .map(|(.., electrons)| electrons)
+            .sum();
+        assert_eq!(placed, 56);
+        assert!(configuration(118)
+            .iter()
+            .all(|&(_, l, _, electrons)| l <= 2 && electrons <= 2));
+    }
+}
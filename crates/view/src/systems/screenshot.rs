@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{save_to_disk, Screenshot},
+};
+use bevy_blendy_cameras::OrbitCameraController;
+use mechanics::particle::Kind;
+use mechanics::{PhysicsConstants, SimRng, SimStats, SimStep};
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::ScreenshotSettings;
+
+/// Everything needed to reproduce a screenshot, written next to it as JSON
+#[derive(Serialize)]
+struct ScreenshotMetadata {
+    step: u64,
+    seed: u64,
+    physics: PhysicsConstants,
+    particle_counts: BTreeMap<String, usize>,
+    free_nucleons: usize,
+    atom_count: usize,
+    elements: BTreeMap<String, usize>,
+    camera: CameraMetadata,
+}
+
+#[derive(Serialize)]
+struct CameraMetadata {
+    translation: [f32; 3],
+    /// Quaternion as x, y, z, w
+    rotation: [f32; 4],
+    focus: [f32; 3],
+    radius: f32,
+}
+
+// system that saves the current frame and its metadata on pressing F12
+pub fn take_screenshot(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    camera: Query<(&GlobalTransform, &OrbitCameraController)>,
+    settings: Res<ScreenshotSettings>,
+    step: Res<SimStep>,
+    rng: Res<SimRng>,
+    physics: Res<PhysicsConstants>,
+    stats: Res<SimStats>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F12) {
+        return;
+    }
+    let Ok((transform, controller)) = camera.get_single() else {
+        return;
+    };
+    if let Err(err) = std::fs::create_dir_all(&settings.directory) {
+        error!(
+            "Failed to create screenshot directory {}: {err}",
+            settings.directory.display()
+        );
+        return;
+    }
+
+    // Several screenshots can be taken on the same step, while paused or in
+    // earlier runs, so count up to the first free name
+    let mut index = 0;
+    let (image_path, metadata_path) = loop {
+        let name = format!("screenshot_{:08}_{index:03}", step.0);
+        let image = settings.directory.join(format!("{name}.png"));
+        let metadata = settings.directory.join(format!("{name}.json"));
+        if !image.exists() && !metadata.exists() {
+            break (image, metadata);
+        }
+        index += 1;
+    };
+
+    let (_, rotation, translation) = transform.to_scale_rotation_translation();
+    let metadata = ScreenshotMetadata {
+        step: step.0,
+        seed: rng.seed(),
+        physics: *physics,
+        particle_counts: Kind::iter()
+            .map(|kind| (format!("{kind:?}"), stats.particle_count(kind)))
+            .collect(),
+        free_nucleons: stats.free_nucleons,
+        atom_count: stats.atom_count,
+        elements: stats
+            .element_counts
+            .iter()
+            .map(|(element, count)| (element.symbol().to_string(), *count))
+            .collect(),
+        camera: CameraMetadata {
+            translation: translation.to_array(),
+            rotation: rotation.to_array(),
+            focus: controller.target_focus.to_array(),
+            radius: controller.target_radius,
+        },
+    };
+    let written = serde_json::to_string_pretty(&metadata)
+        .map_err(|err| err.to_string())
+        .and_then(|json| {
+            std::fs::write(&metadata_path, json).map_err(|err| err.to_string())
+        });
+    if let Err(err) = written {
+        error!(
+            "Failed to write screenshot metadata {}: {err}",
+            metadata_path.display()
+        );
+    }
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(image_path));
+}
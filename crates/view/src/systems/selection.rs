@@ -0,0 +1,183 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_dynamics::Velocity;
+use mechanics::particle::Particle;
+use mechanics::prefab::Prefab;
+use mechanics::{Atom, Frozen, Selected};
+
+use crate::{ColorOverride, MainCamera, Marquee, Selection, SelectionCommand};
+
+/// Where saved prefabs are written
+const PREFAB_DIR: &str = "prefabs";
+/// Distance in front of the camera the marquee is drawn at
+const MARQUEE_DEPTH: f32 = 1.0;
+// Lavender
+const SELECTION_COLOR: Color = Color::srgb(0.706, 0.745, 0.996);
+
+/// Selects `entity` alone, or deselects it if it already was selected.
+/// With `additive` the rest of the selection is kept.
+pub fn toggle_selection(
+    commands: &mut Commands,
+    selected: &Query<Entity, With<Selected>>,
+    entity: Entity,
+    additive: bool,
+) {
+    let was_selected = selected.contains(entity);
+
+    if additive {
+        if was_selected {
+            commands.entity(entity).remove::<Selected>();
+        }
+    } else {
+        // the colour of atoms follows in `maintain_atom_hitbox_colour`
+        clear_selection(commands, selected);
+    }
+
+    if !was_selected {
+        commands.entity(entity).insert(Selected);
+    }
+}
+
+/// Deselects everything
+pub fn clear_selection(
+    commands: &mut Commands,
+    selected: &Query<Entity, With<Selected>>,
+) {
+    for entity in selected.iter() {
+        commands.entity(entity).remove::<Selected>();
+    }
+}
+
+/// Selects `entity` alone, keeping it selected if it already was
+pub fn select_only(
+    commands: &mut Commands,
+    selected: &Query<Entity, With<Selected>>,
+    entity: Entity,
+) {
+    for other in selected.iter().filter(|other| *other != entity) {
+        commands.entity(other).remove::<Selected>();
+    }
+    commands.entity(entity).insert(Selected);
+}
+
+pub fn sync_selection(
+    selected: Query<(Entity, Option<&Atom>), With<Selected>>,
+    mut selection: ResMut<Selection>,
+) {
+    let mut current = Selection::default();
+    for (entity, atom) in selected.iter() {
+        if let Some(atom) = atom {
+            current.atoms.insert(entity);
+            current
+                .members
+                .extend(atom.constituent_particles.iter().copied());
+        } else {
+            current.particles.insert(entity);
+            current.members.insert(entity);
+        }
+    }
+    selection.set_if_neq(current);
+}
+
+/// Rings the particles selected on their own, whose atoms have no hitbox to
+/// show it, and draws the marquee while it is dragged
+pub fn draw_selection(
+    mut gizmos: Gizmos,
+    selection: Res<Selection>,
+    particles: Query<(&Particle, &GlobalTransform)>,
+    marquee: Res<Marquee>,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    for entity in &selection.particles {
+        if let Ok((particle, transform)) = particles.get(*entity) {
+            gizmos.sphere(
+                Isometry3d::from_translation(transform.translation()),
+                particle.radius * 1.5,
+                SELECTION_COLOR,
+            );
+        }
+    }
+
+    if !marquee.is_dragging() {
+        return;
+    }
+    let Ok((camera, transform)) = camera.get_single() else {
+        return;
+    };
+    let outline: Vec<Vec3> = marquee
+        .outline()
+        .into_iter()
+        .filter_map(|point| camera.viewport_to_world(transform, point).ok())
+        .map(|ray| ray.get_point(MARQUEE_DEPTH))
+        .collect();
+    gizmos.linestrip(
+        outline.iter().chain(outline.first()).copied(),
+        SELECTION_COLOR,
+    );
+}
+
+/// Applies [`SelectionCommand`]s to every particle in the [`Selection`]
+pub fn handle_selection_commands(
+    mut events: EventReader<SelectionCommand>,
+    mut commands: Commands,
+    selection: Res<Selection>,
+    particles: Query<(&Particle, &Transform, &Velocity, Has<Frozen>)>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    for command in events.read() {
+        match command {
+            SelectionCommand::ToggleFrozen => {
+                let frozen = selection.members.iter().all(|entity| {
+                    particles.get(*entity).is_ok_and(|(.., frozen)| frozen)
+                });
+                for entity in &selection.members {
+                    if let Some(mut entity) = commands.get_entity(*entity) {
+                        if frozen {
+                            entity.remove::<Frozen>();
+                        } else {
+                            entity.insert(Frozen);
+                        }
+                    }
+                }
+            }
+            SelectionCommand::Recolour(color) => {
+                for entity in &selection.members {
+                    if let Some(mut entity) = commands.get_entity(*entity) {
+                        match color {
+                            Some(color) => {
+                                entity.insert(ColorOverride(*color));
+                            }
+                            None => {
+                                entity.remove::<ColorOverride>();
+                            }
+                        }
+                    }
+                }
+            }
+            SelectionCommand::SavePrefab(name) => {
+                let prefab = Prefab::capture(
+                    selection
+                        .members
+                        .iter()
+                        .filter_map(|entity| particles.get(*entity).ok())
+                        .map(|(particle, transform, velocity, _)| {
+                            (particle, transform, velocity)
+                        }),
+                );
+                let path = Path::new(PREFAB_DIR).join(format!("{name}.ron"));
+                match prefab.save(&path) {
+                    Ok(()) => info!(
+                        "Saved {} particles to {}",
+                        prefab.particles.len(),
+                        path.display()
+                    ),
+                    Err(err) => error!("{err}"),
+                }
+            }
+            SelectionCommand::Clear => {
+                clear_selection(&mut commands, &selected);
+            }
+        }
+    }
+}
@@ -0,0 +1,604 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    ecs::system::SystemParam,
+    picking::{focus::HoverMap, pointer::PointerId},
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_blendy_cameras::OrbitCameraController;
+use bevy_dynamics::Velocity;
+use mechanics::particle::{Kind, Particle};
+use mechanics::snapshot::ParticleState;
+use mechanics::{
+    spawn_particle, Atom, ExternalForce, PhysicsConstants, Selected, SimRng,
+};
+use rand::Rng;
+
+use crate::{
+    clear_selection, MainCamera, Marquee, Selection, ToolAction, ToolCommand,
+    ToolMode, Tools, UndoStack,
+};
+
+const TOOL_KEYS: [(KeyCode, ToolMode); 6] = [
+    (KeyCode::Digit1, ToolMode::Select),
+    (KeyCode::Digit2, ToolMode::Spawn),
+    (KeyCode::Digit3, ToolMode::Brush),
+    (KeyCode::Digit4, ToolMode::Drag),
+    (KeyCode::Digit5, ToolMode::Delete),
+    (KeyCode::Digit6, ToolMode::Fire),
+];
+/// Distance in front of the camera fired particles start at, so they don't
+/// appear inside the near plane
+const FIRE_OFFSET: f32 = 5.0;
+
+/// The mouse as the tools see it: the view ray under the cursor and what
+/// it hovers
+#[derive(SystemParam)]
+pub struct ScenePointer<'w, 's> {
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    window: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    camera: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            &'static OrbitCameraController,
+        ),
+        With<MainCamera>,
+    >,
+    hover_map: Res<'w, HoverMap>,
+    nodes: Query<'w, 's, (), With<Node>>,
+}
+
+impl ScenePointer<'_, '_> {
+    fn hits(&self) -> impl Iterator<Item = (Entity, f32)> + '_ {
+        self.hover_map
+            .get(&PointerId::Mouse)
+            .into_iter()
+            .flat_map(|hits| {
+                hits.iter().map(|(entity, hit)| (*entity, hit.depth))
+            })
+    }
+
+    /// Whether the cursor is over a HUD panel rather than the scene
+    fn over_ui(&self) -> bool {
+        self.hits().any(|(entity, _)| self.nodes.contains(entity))
+    }
+
+    /// Whether the left button went down over the scene this frame
+    fn clicked(&self) -> bool {
+        self.mouse.just_pressed(MouseButton::Left) && !self.over_ui()
+    }
+
+    fn held(&self) -> bool {
+        self.mouse.pressed(MouseButton::Left)
+    }
+
+    /// The nearest hovered entity that passes `filter`
+    fn nearest(&self, filter: impl Fn(Entity) -> bool) -> Option<Entity> {
+        self.hits()
+            .filter(|(entity, _)| filter(*entity))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity, _)| entity)
+    }
+
+    fn cursor(&self) -> Option<Vec2> {
+        self.window.get_single().ok()?.cursor_position()
+    }
+
+    fn ray(&self) -> Option<Ray3d> {
+        let cursor = self.cursor()?;
+        let (camera, transform, _) = self.camera.get_single().ok()?;
+        camera.viewport_to_world(transform, cursor).ok()
+    }
+
+    /// Where `point` appears on screen, in the same space as the cursor
+    fn project(&self, point: Vec3) -> Option<Vec2> {
+        let (camera, transform, _) = self.camera.get_single().ok()?;
+        camera.world_to_viewport(transform, point).ok()
+    }
+
+    /// Where the cursor meets the work plane, the horizontal plane through
+    /// the orbit pivot
+    fn on_work_plane(&self) -> Option<Vec3> {
+        let ray = self.ray()?;
+        let (_, _, controller) = self.camera.get_single().ok()?;
+        ray.intersect_plane(
+            controller.target_focus,
+            InfinitePlane3d::new(Vec3::Y),
+        )
+        .map(|distance| ray.get_point(distance))
+    }
+
+    /// Where the cursor meets the plane through `point` facing the camera
+    fn facing_plane(&self, point: Vec3) -> Option<Vec3> {
+        let ray = self.ray()?;
+        let (_, transform, _) = self.camera.get_single().ok()?;
+        let plane = InfinitePlane3d {
+            normal: transform.forward(),
+        };
+        ray.intersect_plane(point, plane)
+            .map(|distance| ray.get_point(distance))
+    }
+}
+
+/// Spawns particles for the tools with the current damping
+#[derive(SystemParam)]
+pub struct ParticleSpawner<'w, 's> {
+    commands: Commands<'w, 's>,
+    physics: Res<'w, PhysicsConstants>,
+}
+
+impl ParticleSpawner<'_, '_> {
+    fn spawn(&mut self, kind: Kind, position: Vec3, velocity: Vec3) -> Entity {
+        spawn_particle(
+            &mut self.commands,
+            kind,
+            position,
+            velocity,
+            self.physics.damping,
+        )
+    }
+}
+
+/// Particles held by the drag tool
+#[derive(Debug, Default)]
+pub struct DragGrab {
+    /// Each particle with its offset from the centre of the grab
+    particles: Vec<(Entity, Vec3)>,
+    /// Where the particles were picked up, for undo
+    start: Vec<(Entity, Vec3)>,
+}
+
+/// Particles sprayed by the brush since the button went down
+#[derive(Debug, Default)]
+pub struct BrushStroke {
+    active: bool,
+    spawned: Vec<Entity>,
+    /// Fraction of a particle carried over to the next frame
+    pending: f32,
+}
+
+fn particle_state(
+    particle: &Particle,
+    transform: &Transform,
+    velocity: &Velocity,
+) -> ParticleState {
+    ParticleState {
+        kind: particle.kind,
+        position: transform.translation.to_array(),
+        velocity: velocity.value.to_array(),
+    }
+}
+
+/// Particles `entity` stands for, itself or the nucleons of an atom
+fn grabbed_particles(entity: Entity, atoms: &Query<&Atom>) -> Vec<Entity> {
+    atoms.get(entity).map_or_else(
+        |_| vec![entity],
+        |atom| atom.constituent_particles.clone(),
+    )
+}
+
+/// What the drag tool moves when it grabs a particle or atom: the whole
+/// [`Selection`] if it is part of it, otherwise just what it stands for
+#[derive(SystemParam)]
+pub struct DragTargets<'w, 's> {
+    atoms: Query<'w, 's, &'static Atom>,
+    selection: Res<'w, Selection>,
+}
+
+impl DragTargets<'_, '_> {
+    fn particles(&self, entity: Entity) -> Vec<Entity> {
+        let selection = &self.selection;
+        if selection.atoms.contains(&entity)
+            || selection.members.contains(&entity)
+        {
+            selection.members.iter().copied().collect()
+        } else {
+            grabbed_particles(entity, &self.atoms)
+        }
+    }
+}
+
+// system that picks a tool on pressing 1-6, cycles the spawned kind on
+// pressing Q, undoes on pressing Ctrl + Z and deletes the selection on
+// pressing Delete
+pub fn tool_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut tools: ResMut<Tools>,
+    mut commands: EventWriter<ToolCommand>,
+) {
+    let shift = keyboard_input.pressed(KeyCode::ShiftLeft)
+        || keyboard_input.pressed(KeyCode::ShiftRight);
+    let ctrl = keyboard_input.pressed(KeyCode::ControlLeft)
+        || keyboard_input.pressed(KeyCode::ControlRight);
+
+    // Shift + 1-4 toggle trails
+    if !shift {
+        for (key, mode) in TOOL_KEYS {
+            if keyboard_input.just_pressed(key) {
+                tools.mode = mode;
+            }
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyQ) {
+        tools.cycle_kind();
+    }
+
+    if ctrl && keyboard_input.just_pressed(KeyCode::KeyZ) {
+        commands.send(ToolCommand::Undo);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Delete) {
+        commands.send(ToolCommand::DeleteSelection);
+    }
+}
+
+/// Drags a box, or a lasso with Alt, around the atoms and free particles to
+/// select. Shift adds to the selection, and a click on empty space clears
+/// it. Clicks on atoms and particles are left to their observers.
+pub fn select_tool(
+    mut commands: Commands,
+    pointer: ScenePointer,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    tools: Res<Tools>,
+    mut marquee: ResMut<Marquee>,
+    targets: Query<
+        (Entity, &GlobalTransform, Option<&Atom>),
+        Or<(With<Particle>, With<Atom>)>,
+    >,
+    selected: Query<Entity, With<Selected>>,
+) {
+    if tools.mode != ToolMode::Select {
+        if !marquee.points.is_empty() {
+            marquee.points.clear();
+        }
+        return;
+    }
+    let Some(cursor) = pointer.cursor() else {
+        return;
+    };
+
+    if pointer.clicked() {
+        marquee.points = vec![cursor];
+        marquee.lasso = keyboard_input.pressed(KeyCode::AltLeft)
+            || keyboard_input.pressed(KeyCode::AltRight);
+        return;
+    }
+    if marquee.points.is_empty() {
+        return;
+    }
+    if pointer.held() {
+        if marquee.lasso || marquee.points.len() == 1 {
+            marquee.points.push(cursor);
+        } else {
+            marquee.points[1] = cursor;
+        }
+        return;
+    }
+
+    let additive = keyboard_input.pressed(KeyCode::ShiftLeft)
+        || keyboard_input.pressed(KeyCode::ShiftRight);
+    if marquee.is_dragging() {
+        if !additive {
+            clear_selection(&mut commands, &selected);
+        }
+        // Particles bound in an atom are selected through it
+        let bound: HashSet<Entity> = targets
+            .iter()
+            .filter_map(|(_, _, atom)| atom)
+            .flat_map(|atom| atom.constituent_particles.iter().copied())
+            .collect();
+        for (entity, transform, _) in targets.iter() {
+            if bound.contains(&entity) {
+                continue;
+            }
+            if pointer
+                .project(transform.translation())
+                .is_some_and(|point| marquee.contains(point))
+            {
+                commands.entity(entity).insert(Selected);
+            }
+        }
+    } else if !additive
+        && pointer.nearest(|entity| targets.contains(entity)).is_none()
+    {
+        clear_selection(&mut commands, &selected);
+    }
+    marquee.points.clear();
+}
+
+/// Places a particle of the chosen kind on the work plane
+pub fn spawn_tool(
+    mut spawner: ParticleSpawner,
+    pointer: ScenePointer,
+    tools: Res<Tools>,
+    mut undo: ResMut<UndoStack>,
+) {
+    if tools.mode != ToolMode::Spawn || !pointer.clicked() {
+        return;
+    }
+    let Some(position) = pointer.on_work_plane() else {
+        return;
+    };
+
+    let entity = spawner.spawn(tools.kind, position, Vec3::ZERO);
+    undo.push(ToolAction::Spawned(vec![entity]));
+}
+
+/// Sprays particles into a sphere around the cursor on the work plane while
+/// the button is held, undone as one action
+pub fn brush_tool(
+    mut spawner: ParticleSpawner,
+    pointer: ScenePointer,
+    tools: Res<Tools>,
+    time: Res<Time>,
+    mut rng: ResMut<SimRng>,
+    mut undo: ResMut<UndoStack>,
+    mut stroke: Local<BrushStroke>,
+) {
+    let brushing = tools.mode == ToolMode::Brush;
+    if brushing && pointer.clicked() {
+        stroke.active = true;
+    }
+
+    if !stroke.active {
+        return;
+    }
+    if !brushing || !pointer.held() {
+        let spawned = std::mem::take(&mut stroke.spawned);
+        if !spawned.is_empty() {
+            undo.push(ToolAction::Spawned(spawned));
+        }
+        *stroke = BrushStroke::default();
+        return;
+    }
+
+    let Some(center) = pointer.on_work_plane() else {
+        return;
+    };
+    stroke.pending += tools.brush_rate * time.delta_secs();
+    while stroke.pending >= 1.0 {
+        stroke.pending -= 1.0;
+        let offset = loop {
+            let offset = Vec3::new(
+                rng.gen_range(-1.0..=1.0),
+                rng.gen_range(-1.0..=1.0),
+                rng.gen_range(-1.0..=1.0),
+            );
+            if offset.length_squared() <= 1.0 {
+                break offset;
+            }
+        };
+        let entity = spawner.spawn(
+            tools.kind,
+            center + offset * tools.brush_radius,
+            Vec3::ZERO,
+        );
+        stroke.spawned.push(entity);
+    }
+}
+
+/// Pulls the grabbed particle, every nucleon of the grabbed atom, or the
+/// whole selection if either is selected, towards the cursor with a
+/// critically damped spring, on the plane through them facing the camera
+pub fn drag_tool(
+    mut commands: Commands,
+    pointer: ScenePointer,
+    tools: Res<Tools>,
+    particles: Query<(&Particle, &Transform, &Velocity)>,
+    targets: DragTargets,
+    mut undo: ResMut<UndoStack>,
+    mut grab: Local<Option<DragGrab>>,
+) {
+    let dragging = tools.mode == ToolMode::Drag;
+    if dragging && pointer.clicked() {
+        let target = pointer
+            .nearest(|entity| particles.contains(entity))
+            .or_else(|| {
+                pointer.nearest(|entity| targets.atoms.contains(entity))
+            });
+        if let Some(target) = target {
+            let start: Vec<(Entity, Vec3)> = targets
+                .particles(target)
+                .into_iter()
+                .filter_map(|entity| {
+                    let (_, transform, _) = particles.get(entity).ok()?;
+                    Some((entity, transform.translation))
+                })
+                .collect();
+            if !start.is_empty() {
+                let center =
+                    start.iter().map(|(_, position)| *position).sum::<Vec3>()
+                        / start.len() as f32;
+                *grab = Some(DragGrab {
+                    particles: start
+                        .iter()
+                        .map(|(entity, position)| (*entity, *position - center))
+                        .collect(),
+                    start,
+                });
+            }
+        }
+    }
+
+    if !dragging || !pointer.held() {
+        if let Some(held) = grab.take() {
+            for (entity, _) in held.particles {
+                if let Some(mut entity) = commands.get_entity(entity) {
+                    entity.remove::<ExternalForce>();
+                }
+            }
+            undo.push(ToolAction::Dragged(held.start));
+        }
+        return;
+    }
+    let Some(held) = grab.as_ref() else {
+        return;
+    };
+
+    let positions: Vec<Vec3> = held
+        .particles
+        .iter()
+        .filter_map(|(entity, _)| particles.get(*entity).ok())
+        .map(|(_, transform, _)| transform.translation)
+        .collect();
+    if positions.is_empty() {
+        return;
+    }
+    let center = positions.iter().sum::<Vec3>() / positions.len() as f32;
+    let Some(cursor) = pointer.facing_plane(center) else {
+        return;
+    };
+
+    let stiffness = tools.drag_stiffness;
+    let damping = 2.0 * stiffness.sqrt();
+    for (entity, offset) in &held.particles {
+        let Ok((particle, transform, velocity)) = particles.get(*entity) else {
+            continue;
+        };
+        let stretch = cursor + *offset - transform.translation;
+        let acceleration = stretch * stiffness - velocity.value * damping;
+        commands
+            .entity(*entity)
+            .try_insert(ExternalForce(acceleration * particle.mass));
+    }
+}
+
+/// Removes the hovered particle, or the hovered atom with its nucleons
+pub fn delete_tool(
+    mut commands: Commands,
+    pointer: ScenePointer,
+    tools: Res<Tools>,
+    particles: Query<(&Particle, &Transform, &Velocity)>,
+    atoms: Query<&Atom>,
+    mut undo: ResMut<UndoStack>,
+) {
+    if tools.mode != ToolMode::Delete || !pointer.clicked() {
+        return;
+    }
+    let target = pointer
+        .nearest(|entity| particles.contains(entity))
+        .or_else(|| pointer.nearest(|entity| atoms.contains(entity)));
+    let Some(target) = target else {
+        return;
+    };
+
+    let deleted: Vec<(Entity, ParticleState)> =
+        grabbed_particles(target, &atoms)
+            .into_iter()
+            .filter_map(|entity| {
+                let (particle, transform, velocity) =
+                    particles.get(entity).ok()?;
+                commands.entity(entity).despawn();
+                Some((entity, particle_state(particle, transform, velocity)))
+            })
+            .collect();
+    if atoms.contains(target) {
+        // Would otherwise linger until the next step while paused
+        commands.entity(target).despawn_recursive();
+    }
+    if !deleted.is_empty() {
+        undo.push(ToolAction::Deleted(deleted));
+    }
+}
+
+/// Shoots a particle of the chosen kind from the camera along the view ray
+pub fn fire_tool(
+    mut spawner: ParticleSpawner,
+    pointer: ScenePointer,
+    tools: Res<Tools>,
+    mut undo: ResMut<UndoStack>,
+) {
+    if tools.mode != ToolMode::Fire || !pointer.clicked() {
+        return;
+    }
+    let Some(ray) = pointer.ray() else {
+        return;
+    };
+
+    let entity = spawner.spawn(
+        tools.kind,
+        ray.get_point(FIRE_OFFSET),
+        *ray.direction * tools.fire_speed,
+    );
+    undo.push(ToolAction::Spawned(vec![entity]));
+}
+
+/// Applies [`ToolCommand`]s, undoing the last action or deleting the
+/// selection
+pub fn handle_tool_commands(
+    mut events: EventReader<ToolCommand>,
+    mut commands: Commands,
+    mut particles: Query<(&Particle, &mut Transform, &mut Velocity)>,
+    selection: Res<Selection>,
+    physics: Res<PhysicsConstants>,
+    mut undo: ResMut<UndoStack>,
+) {
+    for command in events.read() {
+        match command {
+            ToolCommand::Undo => match undo.actions.pop_back() {
+                Some(ToolAction::Spawned(entities)) => {
+                    for entity in entities {
+                        if let Some(entity) = commands.get_entity(entity) {
+                            entity.despawn_recursive();
+                        }
+                    }
+                }
+                Some(ToolAction::Deleted(deleted)) => {
+                    let restored: HashMap<Entity, Entity> = deleted
+                        .into_iter()
+                        .map(|(entity, state)| {
+                            let restored = spawn_particle(
+                                &mut commands,
+                                state.kind,
+                                Vec3::from_array(state.position),
+                                Vec3::from_array(state.velocity),
+                                physics.damping,
+                            );
+                            (entity, restored)
+                        })
+                        .collect();
+                    undo.remap(&restored);
+                }
+                Some(ToolAction::Dragged(start)) => {
+                    for (entity, position) in start {
+                        if let Ok((_, mut transform, mut velocity)) =
+                            particles.get_mut(entity)
+                        {
+                            transform.translation = position;
+                            velocity.value = Vec3::ZERO;
+                        }
+                    }
+                }
+                None => info!("Nothing to undo"),
+            },
+            ToolCommand::DeleteSelection => {
+                let deleted: Vec<(Entity, ParticleState)> = selection
+                    .members
+                    .iter()
+                    .filter_map(|&entity| {
+                        let (particle, transform, velocity) =
+                            particles.get(entity).ok()?;
+                        commands.entity(entity).despawn();
+                        Some((
+                            entity,
+                            particle_state(particle, transform, velocity),
+                        ))
+                    })
+                    .collect();
+                for atom in &selection.atoms {
+                    if let Some(atom) = commands.get_entity(*atom) {
+                        atom.despawn_recursive();
+                    }
+                }
+                if !deleted.is_empty() {
+                    undo.push(ToolAction::Deleted(deleted));
+                }
+            }
+        }
+    }
+}